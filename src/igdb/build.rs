@@ -1,5 +1,164 @@
-use std::io::Result;
+use std::fmt::Write as _;
+use std::io::{Error, ErrorKind, Result};
+use std::path::PathBuf;
+
+use prost::Message;
+use prost_types::field_descriptor_proto::Label;
+use prost_types::field_descriptor_proto::Type;
+use prost_types::{DescriptorProto, FileDescriptorSet};
+
+const PROTO: &str = "protos/igdbapi.proto";
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "private", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
 fn main() -> Result<()> {
-    prost_build::compile_protos(&["protos/igdbapi.proto"], &["protos/"])?;
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    println!("cargo:rerun-if-changed={}", PROTO);
+
+    let descriptors = parse_protos()?;
+    std::fs::write(
+        out_dir.join("igdb_fields.rs"),
+        generate_field_paths(&descriptors),
+    )?;
+
+    let mut config = prost_build::Config::new();
+    add_serde_attributes(&mut config, &descriptors);
+    config.compile_fds(descriptors)?;
+
     Ok(())
 }
+
+/// Parses the proto in Rust rather than shelling out to `protoc`, so the crate builds without a system install of it
+fn parse_protos() -> Result<FileDescriptorSet> {
+    let parsed = protobuf_parse::Parser::new()
+        .pure()
+        .include("protos")
+        .input(PROTO)
+        .file_descriptor_set()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    // protobuf-parse has its own descriptor types, so they go through the wire format to become prost's
+    let bytes = protobuf::Message::write_to_bytes(&parsed)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(FileDescriptorSet::decode(bytes.as_slice())?)
+}
+
+/// Only the messages that are actual IGDB entities get field paths, not the wrappers around responses
+fn is_entity(message: &DescriptorProto) -> bool {
+    let name = message.name();
+    !(name.ends_with("Result") || name == "Count" || name == "MultiQueryResultArray")
+}
+
+/// Makes the generated types (de)serializable in the shape of IGDB's JSON endpoints, which send timestamps as unix
+/// seconds, nested objects as either their id or the whole object depending on whether they were expanded, and
+/// `*Result` wrappers as a bare array
+fn add_serde_attributes(config: &mut prost_build::Config, descriptors: &FileDescriptorSet) {
+    let messages = descriptors
+        .file
+        .iter()
+        .filter(|file| file.package() == "igdb")
+        .flat_map(|file| file.message_type.iter());
+
+    for message in messages {
+        let path = format!(".igdb.{}", message.name());
+        config.message_attribute(&path, "#[derive(serde::Serialize, serde::Deserialize)]");
+        if message.name().ends_with("Result") && message.field.len() == 1 {
+            config.message_attribute(&path, "#[serde(transparent)]");
+        } else {
+            config.message_attribute(&path, "#[serde(default)]");
+        }
+
+        for field in &message.field {
+            let with = match (field.r#type(), field.type_name(), field.label()) {
+                (Type::Message, ".google.protobuf.Timestamp", _) => "timestamp",
+                (Type::Message, _, Label::Repeated) => "expandable_list",
+                (Type::Message, _, _) => "expandable",
+                _ => continue,
+            };
+
+            config.field_attribute(
+                format!("{}.{}", path, field.name()),
+                format!("#[serde(with = \"crate::json::{}\")]", with),
+            );
+        }
+    }
+}
+
+fn method_name(field: &str) -> String {
+    if RUST_KEYWORDS.contains(&field) {
+        format!("r#{}", field)
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Writes a struct per IGDB entity with a method per field, so field paths like `cover.image_id` are checked against
+/// the proto by the compiler instead of being discovered as IGDB errors at runtime
+fn generate_field_paths(descriptors: &FileDescriptorSet) -> String {
+    let mut code = String::new();
+
+    let messages = descriptors
+        .file
+        .iter()
+        .filter(|file| file.package() == "igdb")
+        .flat_map(|file| file.message_type.iter())
+        .filter(|message| is_entity(message));
+
+    for message in messages {
+        let name = message.name();
+        write!(
+            code,
+            r#"/// Field paths for [`crate::api::{name}`]
+pub struct {name}(FieldPath);
+
+impl {name} {{
+    pub fn root() -> Self {{ Self(FieldPath::root()) }}
+
+    /// Path of this object itself, which selects only its id when nested
+    pub fn path(&self) -> FieldPath {{ self.0.clone() }}
+
+    /// Every field of this object, e.g. `release_dates.*`
+    pub fn all_fields(&self) -> FieldPath {{ self.0.join("*") }}
+
+"#,
+            name = name
+        )
+        .unwrap();
+
+        for field in &message.field {
+            let method = method_name(field.name());
+            let nested = match (field.r#type(), field.type_name()) {
+                (Type::Message, type_name) => type_name.strip_prefix(".igdb."),
+                _ => None,
+            };
+
+            match nested {
+                Some(nested) => writeln!(
+                    code,
+                    "    pub fn {}(&self) -> {} {{ {}(self.0.join(\"{}\")) }}",
+                    method,
+                    nested,
+                    nested,
+                    field.name()
+                ),
+                None => writeln!(
+                    code,
+                    "    pub fn {}(&self) -> FieldPath {{ self.0.join(\"{}\") }}",
+                    method,
+                    field.name()
+                ),
+            }
+            .unwrap();
+        }
+
+        writeln!(code, "}}\n").unwrap();
+    }
+
+    code
+}
@@ -0,0 +1,565 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prost::bytes::Bytes;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncBufReadExt;
+use tokio::time::Instant;
+
+/// Disk writes between sweeps for expired entries, counting from the first
+const DISK_SWEEP_INTERVAL: u64 = 100;
+
+/// Tells apart the temporary files of concurrent writes, together with the process id
+static PARTIAL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Opt-in caching of successful IGDB responses, see [`crate::client::IGDBClientBuilder::cache`]
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Most responses kept in memory before the least recently used one is dropped. `0` turns the memory tier off.
+    pub capacity: usize,
+    pub default_ttl: Duration,
+    /// TTLs for specific endpoints (e.g. `release_dates` or `games/count`), overriding `default_ttl`. A zero TTL
+    /// turns caching off for that endpoint.
+    pub endpoint_ttls: HashMap<String, Duration>,
+    /// Directory to also keep responses in, so a restart doesn't start with a cold cache
+    pub disk_dir: Option<PathBuf>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            capacity: 1000,
+            default_ttl: Duration::from_secs(60 * 60),
+            endpoint_ttls: HashMap::new(),
+            disk_dir: None,
+        }
+    }
+}
+
+impl CacheConfig {
+    fn ttl_for(&self, endpoint: &str) -> Duration {
+        self.endpoint_ttls
+            .get(endpoint)
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits that missed memory but were found on disk, also counted in `hits`
+    pub disk_hits: u64,
+}
+
+/// Collapses whitespace outside of string literals, so queries that only differ in formatting share an entry
+fn normalize_query(query: &str) -> String {
+    let mut normalized = String::with_capacity(query.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut pending_space = false;
+
+    for c in query.trim().chars() {
+        if in_string {
+            normalized.push(c);
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        if pending_space && c != ';' {
+            normalized.push(' ');
+        }
+        pending_space = false;
+
+        if c == '"' {
+            in_string = true;
+        }
+        normalized.push(c);
+    }
+
+    normalized
+}
+
+struct MemoryEntry {
+    body: Bytes,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+/// Least recently used eviction by scanning for the oldest entry, which is cheap enough at the sizes the bot uses
+struct MemoryTier {
+    entries: HashMap<String, MemoryEntry>,
+    clock: u64,
+}
+
+impl MemoryTier {
+    fn get(&mut self, key: &str) -> Option<Bytes> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        if Instant::now() >= entry.expires_at {
+            self.entries.remove(key);
+            return None;
+        }
+
+        entry.last_used = self.clock;
+        Some(entry.body.clone())
+    }
+
+    fn insert(&mut self, key: String, body: Bytes, ttl: Duration, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(
+            key,
+            MemoryEntry {
+                body,
+                expires_at: Instant::now() + ttl,
+                last_used: self.clock,
+            },
+        );
+    }
+}
+
+/// One file per response, named after a SHA-256 of its key so names stay the same across Rust releases. The key is
+/// stored in the file too, so a hash collision is just a miss.
+struct DiskTier {
+    dir: PathBuf,
+    writes: AtomicU64,
+}
+
+impl DiskTier {
+    fn new(dir: PathBuf) -> Self {
+        DiskTier {
+            dir,
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = Sha256::digest(key.as_bytes());
+        self.dir.join(format!("{:x}.cache", hash))
+    }
+
+    fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+
+    /// Body and remaining TTL of the entry for `key`
+    async fn get(&self, key: &str) -> Option<(Bytes, Duration)> {
+        let path = self.path(key);
+        let contents = tokio::fs::read(&path).await.ok()?;
+        let (expires_at, stored_key, body) = Self::parse(&contents)?;
+        if stored_key != key {
+            return None;
+        }
+
+        let now = Self::unix_now();
+        if now >= expires_at {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+
+        Some((body, Duration::from_secs(expires_at - now)))
+    }
+
+    fn parse(contents: &[u8]) -> Option<(u64, &str, Bytes)> {
+        let mut parts = contents.splitn(3, |&b| b == b'\n');
+        let expires_at = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+        let key = std::str::from_utf8(parts.next()?).ok()?;
+        let body = Bytes::copy_from_slice(parts.next()?);
+        Some((expires_at, key, body))
+    }
+
+    async fn insert(&self, key: &str, body: &[u8], ttl: Duration) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let mut contents = format!("{}\n{}\n", Self::unix_now() + ttl.as_secs(), key).into_bytes();
+        contents.extend_from_slice(body);
+
+        // Written to the side and renamed into place, so a crash mid-write never leaves a truncated entry behind.
+        // Every write gets its own temporary file, so concurrent writes of the same key don't clobber each other.
+        let path = self.path(key);
+        let partial = path.with_extension(format!(
+            "{}-{}.partial",
+            std::process::id(),
+            PARTIAL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        tokio::fs::write(&partial, contents).await?;
+        if let Err(e) = tokio::fs::rename(&partial, &path).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        }
+
+        if self
+            .writes
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(DISK_SWEEP_INTERVAL)
+        {
+            self.sweep().await?;
+        }
+        Ok(())
+    }
+
+    /// Deletes expired entries, which would otherwise only go away when they're read again
+    async fn sweep(&self) -> std::io::Result<()> {
+        let now = Self::unix_now();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "cache")
+                && Self::expires_at(&path)
+                    .await
+                    .is_none_or(|expires_at| now >= expires_at)
+            {
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads only the first line, so sweeping doesn't load every response body; `None` if it isn't an entry
+    async fn expires_at(path: &Path) -> Option<u64> {
+        let file = tokio::fs::File::open(path).await.ok()?;
+        let mut line = String::new();
+        tokio::io::BufReader::new(file)
+            .read_line(&mut line)
+            .await
+            .ok()?;
+        line.trim_end().parse().ok()
+    }
+}
+
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    memory: Mutex<MemoryTier>,
+    disk: Option<DiskTier>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    disk_hits: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        let disk = config.disk_dir.clone().map(DiskTier::new);
+
+        ResponseCache {
+            config,
+            memory: Mutex::new(MemoryTier {
+                entries: HashMap::new(),
+                clock: 0,
+            }),
+            disk,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            disk_hits: AtomicU64::new(0),
+        }
+    }
+
+    /// `resource` is whatever identifies the response format as well as the endpoint, e.g. `games.pb`
+    pub fn key(resource: &str, query: &str) -> String {
+        format!("{} {}", resource, normalize_query(query))
+    }
+
+    pub fn is_enabled_for(&self, endpoint: &str) -> bool {
+        !self.config.ttl_for(endpoint).is_zero()
+    }
+
+    pub async fn get(&self, endpoint: &str, key: &str) -> Option<Bytes> {
+        if let Some(body) = self.memory.lock().unwrap().get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(body);
+        }
+
+        if let Some(disk) = &self.disk {
+            if let Some((body, remaining)) = disk.get(key).await {
+                let ttl = remaining.min(self.config.ttl_for(endpoint));
+                self.memory.lock().unwrap().insert(
+                    key.to_owned(),
+                    body.clone(),
+                    ttl,
+                    self.config.capacity,
+                );
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.disk_hits.fetch_add(1, Ordering::Relaxed);
+                return Some(body);
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub async fn insert(&self, endpoint: &str, key: String, body: Bytes) {
+        let ttl = self.config.ttl_for(endpoint);
+
+        if let Some(disk) = &self.disk {
+            if let Err(e) = disk.insert(&key, &body, ttl).await {
+                tracing::debug!(error = %e, "failed to write IGDB response to the disk cache");
+            }
+        }
+
+        self.memory
+            .lock()
+            .unwrap()
+            .insert(key, body, ttl, self.config.capacity);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use prost::bytes::Bytes;
+    use wiremock::MockServer;
+
+    use crate::api::{Game, GameResult};
+    use crate::client::IGDBClient;
+    use crate::mock_server::*;
+
+    use super::{normalize_query, CacheConfig, CacheStats, DiskTier, ResponseCache};
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("igdb-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn cached_client(server: &MockServer, config: CacheConfig) -> IGDBClient {
+        IGDBClient::builder(TEST_CLIENT_ID.to_owned(), TEST_CLIENT_SECRET.to_owned())
+            .api_url(&format!("{}/v4/", server.uri()))
+            .token_url(&format!("{}/oauth2/token", server.uri()))
+            .cache(config)
+            .build()
+    }
+
+    async fn mock_games(server: &MockServer) {
+        mock_token_endpoint(server).await;
+        let fixture = GameResult {
+            games: vec![final_fantasy_vii()],
+        };
+        mock_endpoint(server, "games", &fixture).await;
+    }
+
+    async fn query_games(client: &IGDBClient, query: &str) -> Vec<Game> {
+        client.games(query.to_owned()).await.unwrap()
+    }
+
+    #[test]
+    fn normalizes_whitespace_outside_strings() {
+        assert_eq!(
+            r#"fields name; search "Final   Fantasy";"#,
+            normalize_query("  fields   name ;\n search\t\"Final   Fantasy\";  ")
+        );
+        assert_eq!(
+            r#"where name = "a \" ;  b";"#,
+            normalize_query(r#"where  name = "a \" ;  b" ;"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_queries_are_served_from_memory() {
+        let server = MockServer::start().await;
+        mock_games(&server).await;
+
+        let client = cached_client(&server, CacheConfig::default());
+        let first = query_games(&client, "fields *; where id = 427;").await;
+        let second = query_games(&client, "fields *;\n  where id = 427;").await;
+
+        assert_eq!(first, second);
+        assert_eq!(1, requests_to(&server, "/v4/games.pb").await);
+        assert_eq!(
+            Some(CacheStats {
+                hits: 1,
+                misses: 1,
+                disk_hits: 0
+            }),
+            client.cache_stats()
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_endpoints_are_not_cached() {
+        let server = MockServer::start().await;
+        mock_games(&server).await;
+
+        let config = CacheConfig {
+            endpoint_ttls: HashMap::from([("games".to_owned(), Duration::ZERO)]),
+            ..Default::default()
+        };
+        let client = cached_client(&server, config);
+        query_games(&client, "fields *;").await;
+        query_games(&client, "fields *;").await;
+
+        assert_eq!(2, requests_to(&server, "/v4/games.pb").await);
+    }
+
+    #[tokio::test]
+    async fn disk_tier_survives_a_new_client() {
+        let server = MockServer::start().await;
+        mock_games(&server).await;
+
+        let dir = scratch_dir("restart");
+        let config = CacheConfig {
+            disk_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+
+        query_games(&cached_client(&server, config.clone()), "fields *;").await;
+
+        let restarted = cached_client(&server, config);
+        let games = query_games(&restarted, "fields *;").await;
+
+        assert_eq!("Final Fantasy VII", games[0].name);
+        assert_eq!(1, requests_to(&server, "/v4/games.pb").await);
+        assert_eq!(1, restarted.cache_stats().unwrap().disk_hits);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn entries_expire_after_their_endpoint_ttl() {
+        let config = CacheConfig {
+            endpoint_ttls: HashMap::from([("games".to_owned(), Duration::from_secs(60))]),
+            ..Default::default()
+        };
+        let cache = ResponseCache::new(config);
+        let key = ResponseCache::key("games.pb", "fields *;");
+        cache
+            .insert("games", key.clone(), Bytes::from("body"))
+            .await;
+
+        tokio::time::advance(Duration::from_secs(59)).await;
+        assert!(cache.get("games", &key).await.is_some());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(cache.get("games", &key).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_entry_is_evicted() {
+        let cache = ResponseCache::new(CacheConfig {
+            capacity: 2,
+            ..Default::default()
+        });
+
+        cache
+            .insert("games", "a".to_owned(), Bytes::from("a"))
+            .await;
+        cache
+            .insert("games", "b".to_owned(), Bytes::from("b"))
+            .await;
+        cache.get("games", "a").await;
+        cache
+            .insert("games", "c".to_owned(), Bytes::from("c"))
+            .await;
+
+        assert!(cache.get("games", "a").await.is_some());
+        assert!(cache.get("games", "b").await.is_none());
+        assert!(cache.get("games", "c").await.is_some());
+    }
+
+    #[test]
+    fn disk_file_names_are_stable() {
+        let disk = DiskTier::new(PathBuf::from("cache"));
+        assert_eq!(
+            PathBuf::from(
+                "cache/fc93a953a1e5a40797f1b54225cdd465a735f1beb5221023069149ab97fd601a.cache"
+            ),
+            disk.path("games.pb fields *;")
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_capacity_turns_the_memory_tier_off() {
+        let cache = ResponseCache::new(CacheConfig {
+            capacity: 0,
+            ..Default::default()
+        });
+
+        cache
+            .insert("games", "a".to_owned(), Bytes::from("a"))
+            .await;
+
+        assert!(cache.get("games", "a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_disk_entries_are_swept_on_insert() {
+        let dir = scratch_dir("sweep");
+        std::fs::create_dir_all(&dir).unwrap();
+        let disk = DiskTier::new(dir.clone());
+        let expired = disk.path("expired");
+        std::fs::write(&expired, "1\nexpired\nbody").unwrap();
+
+        disk.insert("fresh", b"body", Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert!(!expired.exists());
+        assert!(disk.path("fresh").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_of_the_same_key_dont_collide() {
+        let dir = scratch_dir("concurrent");
+        let disk = DiskTier::new(dir.clone());
+
+        let writes = (0..8).map(|_| disk.insert("key", b"body", Duration::from_secs(60)));
+        for result in futures::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        assert_eq!(
+            Some(Bytes::from("body")),
+            disk.get("key").await.map(|(body, _)| body)
+        );
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(1, files.len());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
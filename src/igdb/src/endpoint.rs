@@ -0,0 +1,193 @@
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use crate::api::*;
+use crate::client::IGDBClient;
+use crate::errors::IGDBClientError;
+
+/// Associates a generated `*Result` wrapper with the IGDB endpoint that returns it
+pub trait Endpoint: Message + Default + DeserializeOwned {
+    type Item: Message + Default + DeserializeOwned;
+
+    /// Path of the endpoint relative to the API base, without the `.pb` suffix
+    const PATH: &'static str;
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+impl IGDBClient {
+    /// Queries the endpoint for `E` with the output of [`crate::query::QueryBuilder::build`]
+    pub async fn query<E: Endpoint>(&self, query: String) -> Result<Vec<E::Item>, IGDBClientError> {
+        let result = self.query_endpoint::<E>(E::PATH.to_owned(), query).await?;
+        Ok(result.into_items())
+    }
+
+    /// Counts how many items from the endpoint for `E` match `query`, without fetching them
+    pub async fn count<E: Endpoint>(&self, query: String) -> Result<i64, IGDBClientError> {
+        let endpoint = format!("{}/count", E::PATH);
+        let result = self.query_endpoint::<Count>(endpoint, query).await?;
+        Ok(result.count)
+    }
+}
+
+macro_rules! endpoints {
+    ($($result:ident => $item:ident, $path:ident, $field:ident;)*) => {
+        $(
+            impl Endpoint for $result {
+                type Item = $item;
+                const PATH: &'static str = stringify!($path);
+
+                fn into_items(self) -> Vec<$item> {
+                    self.$field
+                }
+            }
+        )*
+
+        impl IGDBClient {
+            $(
+                #[doc = concat!("Queries the `", stringify!($path), "` endpoint")]
+                pub async fn $path(&self, query: String) -> Result<Vec<$item>, IGDBClientError> {
+                    self.query::<$result>(query).await
+                }
+            )*
+        }
+    };
+}
+
+endpoints! {
+    AgeRatingResult => AgeRating, age_ratings, ageratings;
+    AgeRatingContentDescriptionResult => AgeRatingContentDescription,
+        age_rating_content_descriptions, ageratingcontentdescriptions;
+    AlternativeNameResult => AlternativeName, alternative_names, alternativenames;
+    ArtworkResult => Artwork, artworks, artworks;
+    CharacterResult => Character, characters, characters;
+    CharacterMugShotResult => CharacterMugShot, character_mug_shots, charactermugshots;
+    CollectionResult => Collection, collections, collections;
+    CompanyResult => Company, companies, companies;
+    CompanyLogoResult => CompanyLogo, company_logos, companylogos;
+    CompanyWebsiteResult => CompanyWebsite, company_websites, companywebsites;
+    CoverResult => Cover, covers, covers;
+    EventResult => Event, events, events;
+    EventLogoResult => EventLogo, event_logos, eventlogos;
+    EventNetworkResult => EventNetwork, event_networks, eventnetworks;
+    EventNetworkTypeResult => EventNetworkType, network_types, eventnetworktypes;
+    ExternalGameResult => ExternalGame, external_games, externalgames;
+    FranchiseResult => Franchise, franchises, franchises;
+    GameResult => Game, games, games;
+    GameEngineResult => GameEngine, game_engines, gameengines;
+    GameEngineLogoResult => GameEngineLogo, game_engine_logos, gameenginelogos;
+    GameLocalizationResult => GameLocalization, game_localizations, gamelocalizations;
+    GameModeResult => GameMode, game_modes, gamemodes;
+    GameVersionResult => GameVersion, game_versions, gameversions;
+    GameVersionFeatureResult => GameVersionFeature, game_version_features, gameversionfeatures;
+    GameVersionFeatureValueResult => GameVersionFeatureValue, game_version_feature_values, gameversionfeaturevalues;
+    GameVideoResult => GameVideo, game_videos, gamevideos;
+    GenreResult => Genre, genres, genres;
+    InvolvedCompanyResult => InvolvedCompany, involved_companies, involvedcompanies;
+    KeywordResult => Keyword, keywords, keywords;
+    LanguageResult => Language, languages, languages;
+    LanguageSupportResult => LanguageSupport, language_supports, languagesupports;
+    LanguageSupportTypeResult => LanguageSupportType, language_support_types, languagesupporttypes;
+    MultiplayerModeResult => MultiplayerMode, multiplayer_modes, multiplayermodes;
+    PlatformResult => Platform, platforms, platforms;
+    PlatformFamilyResult => PlatformFamily, platform_families, platformfamilies;
+    PlatformLogoResult => PlatformLogo, platform_logos, platformlogos;
+    PlatformVersionResult => PlatformVersion, platform_versions, platformversions;
+    PlatformVersionCompanyResult => PlatformVersionCompany, platform_version_companies, platformversioncompanies;
+    PlatformVersionReleaseDateResult => PlatformVersionReleaseDate,
+        platform_version_release_dates, platformversionreleasedates;
+    PlatformWebsiteResult => PlatformWebsite, platform_websites, platformwebsites;
+    PlayerPerspectiveResult => PlayerPerspective, player_perspectives, playerperspectives;
+    RegionResult => Region, regions, regions;
+    ReleaseDateResult => ReleaseDate, release_dates, releasedates;
+    ReleaseDateStatusResult => ReleaseDateStatus, release_date_statuses, releasedatestatuses;
+    ScreenshotResult => Screenshot, screenshots, screenshots;
+    SearchResult => Search, search, searches;
+    ThemeResult => Theme, themes, themes;
+    WebsiteResult => Website, websites, websites;
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::MockServer;
+
+    use crate::api::{Count, GameResult, ReleaseDate, ReleaseDateResult};
+    use crate::mock_server::*;
+    use crate::query::Query;
+
+    use super::Endpoint;
+
+    #[test]
+    fn endpoint_paths_match_igdb() {
+        assert_eq!("games", GameResult::PATH);
+        assert_eq!("release_dates", ReleaseDateResult::PATH);
+        assert_eq!("network_types", crate::api::EventNetworkTypeResult::PATH);
+        assert_eq!("search", crate::api::SearchResult::PATH);
+    }
+
+    #[tokio::test]
+    async fn games_returns_unwrapped_games() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        let fixture = GameResult {
+            games: vec![final_fantasy_vii()],
+        };
+        mock_endpoint(&server, "games", &fixture).await;
+
+        for transport in TRANSPORTS {
+            let client = offline_client_with_transport(&server, transport);
+            let games = client
+                .games(Query::where_("id = 427").build())
+                .await
+                .unwrap();
+
+            assert_eq!(1, games.len());
+            assert_eq!("Final Fantasy VII", games[0].name);
+        }
+    }
+
+    #[tokio::test]
+    async fn release_dates_hits_release_dates_endpoint() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        let fixture = ReleaseDateResult {
+            releasedates: vec![ReleaseDate {
+                id: 1,
+                human: "Jan 31, 1997".to_owned(),
+                ..Default::default()
+            }],
+        };
+        mock_endpoint(&server, "release_dates", &fixture).await;
+
+        for transport in TRANSPORTS {
+            let client = offline_client_with_transport(&server, transport);
+            let release_dates = client
+                .release_dates(Query::where_("game = 427").build())
+                .await
+                .unwrap();
+
+            assert_eq!("Jan 31, 1997", release_dates[0].human);
+            assert_eq!(
+                1,
+                requests_to(&server, &endpoint_path("release_dates", transport)).await
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn count_hits_count_endpoint() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        mock_endpoint(&server, "release_dates/count", &Count { count: 42 }).await;
+
+        for transport in TRANSPORTS {
+            let client = offline_client_with_transport(&server, transport);
+            let count = client
+                .count::<ReleaseDateResult>(Query::where_("platform = 48").build())
+                .await
+                .unwrap();
+
+            assert_eq!(42, count);
+        }
+    }
+}
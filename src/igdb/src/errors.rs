@@ -2,11 +2,25 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum IGDBClientError {
-    #[error("bad query submitted")]
-    BadQueryError { query: String, endpoint: String },
+    #[error("bad query submitted to {endpoint}: {title}")]
+    BadQueryError {
+        query: String,
+        endpoint: String,
+        title: String,
+        cause: Option<String>,
+    },
 
-    #[error("bad request")]
-    BadRequestError,
+    #[error("bad request ({status}): {message}")]
+    BadRequestError { status: u16, message: String },
+
+    #[error("not authorized to use the IGDB API ({status})")]
+    AuthorizationError { status: u16 },
+
+    #[error("rate limited by IGDB")]
+    RateLimitedError,
+
+    #[error("IGDB failed to handle the request ({status})")]
+    UpstreamError { status: u16 },
 
     #[error("error making request")]
     InternalClientError(#[from] reqwest::Error),
@@ -14,6 +28,24 @@ pub enum IGDBClientError {
     #[error("Unable to get token")]
     TokenError,
 
+    #[error("IGDB sent an empty response from {endpoint}")]
+    EmptyResponseError { endpoint: String },
+
+    #[error("no result named {name} in multiquery response")]
+    MissingMultiQueryResultError { name: String },
+
     #[error("Unable to decode response")]
     ResponseDecodeError(#[from] prost::DecodeError),
+
+    #[error("Unable to decode JSON response")]
+    JsonDecodeError(#[from] serde_json::Error),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    #[error("unknown {name} value {value}")]
+    UnknownEnumValue { name: &'static str, value: i32 },
+
+    #[error("{field} timestamp out of range: {seconds}")]
+    InvalidTimestamp { field: &'static str, seconds: i64 },
 }
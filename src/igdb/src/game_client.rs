@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::TryStreamExt;
+
+use crate::api::{Company, Game, Platform, PlatformResult, ReleaseDate};
+use crate::client::IGDBClient;
+use crate::errors::IGDBClientError;
+use crate::pagination::MAX_PAGE_SIZE;
+use crate::predicate::field;
+use crate::query::{Query, SortDirection};
+use crate::search::SearchOptions;
+
+type Result<T> = std::result::Result<T, IGDBClientError>;
+
+/// Most games [`GameClient::search_games`] returns
+pub const SEARCH_LIMIT: u32 = 10;
+
+/// The IGDB lookups the bot needs, so logic built on them can run against [`crate::testing::FakeGameClient`] instead
+/// of the network
+#[async_trait]
+pub trait GameClient {
+    /// Best matches for `query`, at most [`SEARCH_LIMIT`] of them, with main games before their add-ons
+    async fn search_games(&self, query: &str) -> Result<Vec<Game>>;
+    async fn get_game(&self, id: u64) -> Result<Option<Game>>;
+    async fn get_game_by_slug(&self, slug: &str) -> Result<Option<Game>>;
+    /// Release dates on `platform` after now, soonest first, with the name and slug of each game expanded
+    async fn upcoming_releases(&self, platform: u64, limit: u32) -> Result<Vec<ReleaseDate>>;
+    /// Every platform by name, fetched a page at a time
+    async fn get_platforms(&self) -> Result<Vec<Platform>>;
+    async fn get_companies(&self, ids: &[u64]) -> Result<Vec<Company>>;
+}
+
+#[async_trait]
+impl GameClient for IGDBClient {
+    async fn search_games(&self, query: &str) -> Result<Vec<Game>> {
+        self.search_games_ranked(query, &SearchOptions::default())
+            .await
+    }
+
+    async fn get_game(&self, id: u64) -> Result<Option<Game>> {
+        let games = self
+            .games(Query::where_(field("id").eq(id)).limit(1).build())
+            .await?;
+        Ok(games.into_iter().next())
+    }
+
+    async fn get_game_by_slug(&self, slug: &str) -> Result<Option<Game>> {
+        let games = self
+            .games(Query::where_(field("slug").eq(slug)).limit(1).build())
+            .await?;
+        Ok(games.into_iter().next())
+    }
+
+    async fn upcoming_releases(&self, platform: u64, limit: u32) -> Result<Vec<ReleaseDate>> {
+        let query = Query::select("*")
+            .select("game.name")
+            .select("game.slug")
+            .where_(
+                field("platform")
+                    .eq(platform)
+                    .and(field("date").gt(Utc::now().timestamp())),
+            )
+            .sort("date", SortDirection::Ascending)
+            .limit(limit.clamp(1, MAX_PAGE_SIZE))
+            .build();
+        self.release_dates(query).await
+    }
+
+    async fn get_platforms(&self) -> Result<Vec<Platform>> {
+        self.paginate::<PlatformResult, _, _, _, _, _>(
+            Query::sort("name", SortDirection::Ascending),
+            MAX_PAGE_SIZE,
+        )
+        .try_collect()
+        .await
+    }
+
+    async fn get_companies(&self, ids: &[u64]) -> Result<Vec<Company>> {
+        // One request per page, since IGDB won't return more than a page of results for a single query
+        let mut companies = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_PAGE_SIZE as usize) {
+            let query = Query::where_(field("id").in_(chunk))
+                .limit(chunk.len() as u32)
+                .build();
+            companies.extend(self.companies(query).await?);
+        }
+        Ok(companies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_string, body_string_contains, method, path};
+    use wiremock::{Mock, MockServer};
+
+    use crate::api::{Company, CompanyResult, Game, GameResult, Platform, PlatformResult};
+    use crate::client::Transport;
+    use crate::mock_server::*;
+
+    use super::GameClient;
+
+    #[tokio::test]
+    async fn looks_up_games_by_slug() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let fixture = GameResult {
+            games: vec![Game {
+                slug: "final-fantasy-vii".to_owned(),
+                ..final_fantasy_vii()
+            }],
+        };
+        Mock::given(method("POST"))
+            .and(path("/v4/games.pb"))
+            .and(body_string(
+                r#"fields *; where slug = "final-fantasy-vii"; limit 1;"#,
+            ))
+            .respond_with(fixture_response(&fixture, Transport::Protobuf))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        let game = client.get_game_by_slug("final-fantasy-vii").await.unwrap();
+
+        assert_eq!(Some(427), game.map(|game| game.id));
+    }
+
+    #[tokio::test]
+    async fn no_companies_skips_the_request() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let client = offline_client(&server);
+        assert!(client.get_companies(&[]).await.unwrap().is_empty());
+        assert_eq!(0, requests_to(&server, "/v4/companies.pb").await);
+    }
+
+    #[tokio::test]
+    async fn companies_are_fetched_a_page_at_a_time() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        for (limit, id) in [(500, 1), (1, 501)] {
+            let fixture = CompanyResult {
+                companies: vec![Company {
+                    id,
+                    ..Default::default()
+                }],
+            };
+            Mock::given(method("POST"))
+                .and(path("/v4/companies.pb"))
+                .and(body_string_contains(format!("limit {};", limit)))
+                .respond_with(fixture_response(&fixture, Transport::Protobuf))
+                .mount(&server)
+                .await;
+        }
+
+        let client = offline_client(&server);
+        let ids: Vec<u64> = (1..=501).collect();
+        let companies = client.get_companies(&ids).await.unwrap();
+
+        assert_eq!(
+            vec![1, 501],
+            companies
+                .iter()
+                .map(|company| company.id)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, requests_to(&server, "/v4/companies.pb").await);
+    }
+
+    #[tokio::test]
+    async fn platforms_are_fetched_a_page_at_a_time() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        for (offset, ids) in [(0, 1..=500), (500, 501..=501)] {
+            let fixture = PlatformResult {
+                platforms: ids
+                    .map(|id| Platform {
+                        id,
+                        ..Default::default()
+                    })
+                    .collect(),
+            };
+            Mock::given(method("POST"))
+                .and(path("/v4/platforms.pb"))
+                .and(body_string(format!(
+                    "fields *; sort name asc; limit 500; offset {};",
+                    offset
+                )))
+                .respond_with(fixture_response(&fixture, Transport::Protobuf))
+                .mount(&server)
+                .await;
+        }
+
+        let client = offline_client(&server);
+        let platforms = client.get_platforms().await.unwrap();
+
+        assert_eq!(501, platforms.len());
+        assert_eq!(2, requests_to(&server, "/v4/platforms.pb").await);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::api::{
+    Artwork, CharacterMugShot, CompanyLogo, Cover, EventLogo, GameEngineLogo, PlatformLogo,
+    Screenshot,
+};
+
+const IGDB_IMAGE_URL: &str = "https://images.igdb.com/igdb/image/upload";
+
+/// Sizes the IGDB image server can resize to. The `2x` variants are double resolution for high DPI displays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSize {
+    /// 90 x 128, fit
+    CoverSmall,
+    CoverSmall2x,
+    /// 264 x 374, fit
+    CoverBig,
+    CoverBig2x,
+    /// 569 x 320, lfill, center gravity
+    ScreenshotMed,
+    ScreenshotMed2x,
+    /// 889 x 500, lfill, center gravity
+    ScreenshotBig,
+    ScreenshotBig2x,
+    /// 1280 x 720, lfill, center gravity
+    ScreenshotHuge,
+    ScreenshotHuge2x,
+    /// 284 x 160, fit
+    LogoMed,
+    LogoMed2x,
+    /// 90 x 90, thumb, center gravity
+    Thumb,
+    Thumb2x,
+    /// 35 x 35, thumb, center gravity
+    Micro,
+    Micro2x,
+    /// 1280 x 720, fit, center gravity
+    Hd720p,
+    Hd720p2x,
+    /// 1920 x 1080, fit, center gravity
+    Hd1080p,
+    Hd1080p2x,
+}
+
+impl ImageSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageSize::CoverSmall => "cover_small",
+            ImageSize::CoverSmall2x => "cover_small_2x",
+            ImageSize::CoverBig => "cover_big",
+            ImageSize::CoverBig2x => "cover_big_2x",
+            ImageSize::ScreenshotMed => "screenshot_med",
+            ImageSize::ScreenshotMed2x => "screenshot_med_2x",
+            ImageSize::ScreenshotBig => "screenshot_big",
+            ImageSize::ScreenshotBig2x => "screenshot_big_2x",
+            ImageSize::ScreenshotHuge => "screenshot_huge",
+            ImageSize::ScreenshotHuge2x => "screenshot_huge_2x",
+            ImageSize::LogoMed => "logo_med",
+            ImageSize::LogoMed2x => "logo_med_2x",
+            ImageSize::Thumb => "thumb",
+            ImageSize::Thumb2x => "thumb_2x",
+            ImageSize::Micro => "micro",
+            ImageSize::Micro2x => "micro_2x",
+            ImageSize::Hd720p => "720p",
+            ImageSize::Hd720p2x => "720p_2x",
+            ImageSize::Hd1080p => "1080p",
+            ImageSize::Hd1080p2x => "1080p_2x",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpg,
+    Png,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+        }
+    }
+}
+
+/// Builds an `images.igdb.com` URL for an image id
+pub fn image_url(image_id: &str, size: ImageSize, format: ImageFormat) -> String {
+    format!(
+        "{}/t_{}/{}.{}",
+        IGDB_IMAGE_URL,
+        size.as_str(),
+        image_id,
+        format.extension()
+    )
+}
+
+/// IGDB objects that point to an image hosted on `images.igdb.com`
+pub trait IgdbImage {
+    fn image_id(&self) -> &str;
+
+    /// URL of the image, or `None` if IGDB didn't send an image id (e.g. the field wasn't selected)
+    fn image_url(&self, size: ImageSize, format: ImageFormat) -> Option<String> {
+        let image_id = self.image_id();
+        if image_id.is_empty() {
+            None
+        } else {
+            Some(image_url(image_id, size, format))
+        }
+    }
+}
+
+macro_rules! igdb_images {
+    ($($image:ty),*) => {
+        $(
+            impl IgdbImage for $image {
+                fn image_id(&self) -> &str {
+                    &self.image_id
+                }
+            }
+        )*
+    };
+}
+
+igdb_images!(
+    Artwork,
+    CharacterMugShot,
+    CompanyLogo,
+    Cover,
+    EventLogo,
+    GameEngineLogo,
+    PlatformLogo,
+    Screenshot
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{Cover, Screenshot};
+
+    use super::{image_url, IgdbImage, ImageFormat, ImageSize};
+
+    #[test]
+    fn builds_cover_url() {
+        let cover = Cover {
+            image_id: "co1wyy".to_owned(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Some("https://images.igdb.com/igdb/image/upload/t_cover_big/co1wyy.jpg".to_owned()),
+            cover.image_url(ImageSize::CoverBig, ImageFormat::Jpg)
+        );
+    }
+
+    #[test]
+    fn builds_retina_urls_in_other_formats() {
+        let screenshot = Screenshot {
+            image_id: "sc6lvm".to_owned(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Some("https://images.igdb.com/igdb/image/upload/t_720p_2x/sc6lvm.webp".to_owned()),
+            screenshot.image_url(ImageSize::Hd720p2x, ImageFormat::Webp)
+        );
+        assert_eq!(
+            "https://images.igdb.com/igdb/image/upload/t_logo_med_2x/cl1.png",
+            image_url("cl1", ImageSize::LogoMed2x, ImageFormat::Png)
+        );
+    }
+
+    #[test]
+    fn missing_image_id_has_no_url() {
+        let cover = Cover::default();
+        assert_eq!(None, cover.image_url(ImageSize::Thumb, ImageFormat::Jpg));
+    }
+}
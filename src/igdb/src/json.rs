@@ -0,0 +1,126 @@
+//! serde helpers for IGDB's JSON endpoints, used by the attributes build.rs puts on the generated types
+
+use serde::de::{DeserializeOwned, Error};
+use serde::Deserialize;
+
+/// Nested objects come back as just their id unless the query expanded them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Expandable<T> {
+    Id(u64),
+    Object(T),
+}
+
+impl<T: DeserializeOwned> Expandable<T> {
+    fn into_object<E: Error>(self) -> Result<T, E> {
+        match self {
+            Expandable::Id(id) => {
+                T::deserialize(serde_json::json!({ "id": id })).map_err(E::custom)
+            }
+            Expandable::Object(object) => Ok(object),
+        }
+    }
+}
+
+pub mod timestamp {
+    use prost_types::Timestamp;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<Timestamp>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_i64(timestamp.seconds),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Timestamp>, D::Error> {
+        let seconds = Option::<i64>::deserialize(deserializer)?;
+        Ok(seconds.map(|seconds| Timestamp { seconds, nanos: 0 }))
+    }
+}
+
+pub mod expandable {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Expandable;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        object: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        object.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<Expandable<T>>::deserialize(deserializer)?
+            .map(Expandable::into_object)
+            .transpose()
+    }
+}
+
+pub mod expandable_list {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Expandable;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        objects: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        objects.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Vec::<Expandable<T>>::deserialize(deserializer)?
+            .into_iter()
+            .map(Expandable::into_object)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{Game, GameResult};
+
+    #[test]
+    fn reads_unexpanded_and_expanded_objects() {
+        let json = r#"[{
+            "id": 427,
+            "name": "Final Fantasy VII",
+            "first_release_date": 854668800,
+            "cover": 82063,
+            "platforms": [6, {"id": 7, "name": "PlayStation"}],
+            "parent_game": {"id": 1, "name": "Parent"},
+            "some_field_added_after_the_proto": true
+        }]"#;
+
+        let result: GameResult = serde_json::from_str(json).unwrap();
+        let game = &result.games[0];
+
+        assert_eq!("Final Fantasy VII", game.name);
+        assert_eq!(854668800, game.first_release_date.as_ref().unwrap().seconds);
+        assert_eq!(82063, game.cover.as_ref().unwrap().id);
+        assert_eq!(6, game.platforms[0].id);
+        assert_eq!("PlayStation", game.platforms[1].name);
+        assert_eq!("Parent", game.parent_game.as_ref().unwrap().name);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let game = crate::mock_server::final_fantasy_vii();
+        let json = serde_json::to_string(&game).unwrap();
+
+        assert_eq!(game, serde_json::from_str::<Game>(&json).unwrap());
+    }
+}
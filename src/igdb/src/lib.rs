@@ -2,6 +2,27 @@ pub mod api {
     include!(concat!(env!("OUT_DIR"), "/igdb.rs"));
 }
 
+pub mod cache;
 pub mod client;
+pub mod endpoint;
 pub mod errors;
+pub mod fields {
+    use crate::query::FieldPath;
+    include!(concat!(env!("OUT_DIR"), "/igdb_fields.rs"));
+}
+pub mod game_client;
+pub mod images;
+mod json;
+#[cfg(test)]
+mod mock_server;
+pub mod model;
+pub mod multiquery;
+pub mod pagination;
+pub mod predicate;
 pub mod query;
+pub mod rate_limit;
+pub mod release_dates;
+pub mod search;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod webhooks;
@@ -0,0 +1,110 @@
+//! Helpers for running [`IGDBClient`] against a local stand-in for IGDB and the Twitch token endpoint
+
+use prost::Message;
+use serde::Serialize;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::api::Game;
+use crate::client::{IGDBClient, IGDBClientBuilder, Transport};
+use crate::rate_limit::RateLimits;
+
+pub const TEST_CLIENT_ID: &str = "test-client-id";
+pub const TEST_CLIENT_SECRET: &str = "test-client-secret";
+pub const TEST_ACCESS_TOKEN: &str = "test-access-token";
+pub const TEST_TOKEN_LIFETIME: u64 = 5587808;
+
+pub const TRANSPORTS: [Transport; 2] = [Transport::Protobuf, Transport::Json];
+
+pub fn token_json() -> serde_json::Value {
+    serde_json::json!({
+        "access_token": TEST_ACCESS_TOKEN,
+        "expires_in": TEST_TOKEN_LIFETIME,
+        "token_type": "bearer",
+    })
+}
+
+pub async fn mock_token_endpoint(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .and(query_param("client_id", TEST_CLIENT_ID))
+        .and(query_param("client_secret", TEST_CLIENT_SECRET))
+        .and(query_param("grant_type", "client_credentials"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(token_json()))
+        .mount(server)
+        .await;
+}
+
+/// Path the client requests `endpoint` from over `transport`
+pub fn endpoint_path(endpoint: &str, transport: Transport) -> String {
+    match transport {
+        Transport::Protobuf => format!("/v4/{}.pb", endpoint),
+        Transport::Json => format!("/v4/{}", endpoint),
+    }
+}
+
+/// Responds with `fixture` encoded the way IGDB sends it over `transport`
+pub fn fixture_response<M: Message + Serialize>(
+    fixture: &M,
+    transport: Transport,
+) -> ResponseTemplate {
+    match transport {
+        Transport::Protobuf => ResponseTemplate::new(200).set_body_bytes(fixture.encode_to_vec()),
+        Transport::Json => ResponseTemplate::new(200).set_body_json(fixture),
+    }
+}
+
+/// Serves `fixture` from `endpoint` over both transports
+pub async fn mock_endpoint<M: Message + Serialize>(
+    server: &MockServer,
+    endpoint: &str,
+    fixture: &M,
+) {
+    for transport in TRANSPORTS {
+        Mock::given(method("POST"))
+            .and(path(endpoint_path(endpoint, transport)))
+            .respond_with(fixture_response(fixture, transport))
+            .mount(server)
+            .await;
+    }
+}
+
+pub fn offline_client(server: &MockServer) -> IGDBClient {
+    offline_client_with_limits(server, RateLimits::default())
+}
+
+pub fn offline_client_with_limits(server: &MockServer, rate_limits: RateLimits) -> IGDBClient {
+    offline_builder(server).rate_limits(rate_limits).build()
+}
+
+pub fn offline_client_with_transport(server: &MockServer, transport: Transport) -> IGDBClient {
+    offline_builder(server).transport(transport).build()
+}
+
+fn offline_builder(server: &MockServer) -> IGDBClientBuilder {
+    IGDBClient::builder(TEST_CLIENT_ID.to_owned(), TEST_CLIENT_SECRET.to_owned())
+        .api_url(&format!("{}/v4/", server.uri()))
+        .token_url(&format!("{}/oauth2/token", server.uri()))
+}
+
+pub async fn requests_to(server: &MockServer, endpoint: &str) -> usize {
+    server
+        .received_requests()
+        .await
+        .expect("request recording is enabled")
+        .iter()
+        .filter(|request| request.url.path() == endpoint)
+        .count()
+}
+
+pub fn final_fantasy_vii() -> Game {
+    Game {
+        id: 427,
+        name: "Final Fantasy VII".to_owned(),
+        first_release_date: Some(prost_types::Timestamp {
+            seconds: 854668800,
+            nanos: 0,
+        }),
+        ..Default::default()
+    }
+}
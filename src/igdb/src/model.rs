@@ -0,0 +1,398 @@
+use chrono::{DateTime, Utc};
+
+use crate::api;
+use crate::errors::ConversionError;
+use crate::release_dates::DatePrecision;
+
+/// Declares a domain enum mirroring a protobuf enum, converting from the raw `i32` IGDB sends
+macro_rules! domain_enum {
+    ($(#[$meta:meta])* $name:ident from $proto:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl TryFrom<i32> for $name {
+            type Error = ConversionError;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                $(
+                    if value == api::$proto::$variant as i32 {
+                        return Ok($name::$variant);
+                    }
+                )*
+
+                Err(ConversionError::UnknownEnumValue {
+                    name: stringify!($name),
+                    value,
+                })
+            }
+        }
+    };
+}
+
+domain_enum!(GameCategory from GameCategoryEnum {
+    MainGame,
+    DlcAddon,
+    Expansion,
+    Bundle,
+    StandaloneExpansion,
+    Mod,
+    Episode,
+    Season,
+    Remake,
+    Remaster,
+    ExpandedGame,
+    Port,
+    Fork,
+    Pack,
+    Update,
+});
+
+// No `Released`, since IGDB sends it as `0` and protobuf can't tell that apart from a missing status
+domain_enum!(GameStatus from GameStatusEnum {
+    Alpha,
+    Beta,
+    EarlyAccess,
+    Offline,
+    Cancelled,
+    Rumored,
+    Delisted,
+});
+
+domain_enum!(Region from RegionRegionEnum {
+    Europe,
+    NorthAmerica,
+    Australia,
+    NewZealand,
+    Japan,
+    China,
+    Asia,
+    Worldwide,
+    Korea,
+    Brazil,
+});
+
+domain_enum!(PlatformCategory from PlatformCategoryEnum {
+    Console,
+    Arcade,
+    Platform,
+    OperatingSystem,
+    PortableConsole,
+    Computer,
+});
+
+/// A [`api::Game`] with `Option`s where protobuf would have sent a default, so missing and zero can be told apart
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    pub id: u64,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub summary: Option<String>,
+    pub storyline: Option<String>,
+    pub url: Option<String>,
+    pub category: GameCategory,
+    /// `None` if the game is released or IGDB doesn't know its status
+    pub status: Option<GameStatus>,
+    pub first_release_date: Option<DateTime<Utc>>,
+    /// IGDB user rating out of 100
+    pub rating: Option<f64>,
+    /// Critic rating out of 100
+    pub aggregated_rating: Option<f64>,
+    pub total_rating: Option<f64>,
+    pub platforms: Vec<Platform>,
+    pub release_dates: Vec<ReleaseDate>,
+    pub involved_companies: Vec<InvolvedCompany>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Platform {
+    pub id: u64,
+    pub name: Option<String>,
+    pub abbreviation: Option<String>,
+    pub slug: Option<String>,
+    pub category: Option<PlatformCategory>,
+    pub generation: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Company {
+    pub id: u64,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    /// ISO 3166-1 numeric country code
+    pub country: Option<u16>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvolvedCompany {
+    pub id: u64,
+    pub company: Option<Company>,
+    pub developer: bool,
+    pub publisher: bool,
+    pub porting: bool,
+    pub supporting: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseDate {
+    pub id: u64,
+    pub region: Option<Region>,
+    pub platform: Option<Platform>,
+    /// Only as precise as `precision` says
+    pub date: Option<DateTime<Utc>>,
+    pub precision: DatePrecision,
+    /// IGDB's own rendering of the date, e.g. "2025-Q3"
+    pub human: Option<String>,
+}
+
+fn text(value: String) -> Option<String> {
+    Some(value).filter(|value| !value.is_empty())
+}
+
+fn non_zero<T: Default + PartialEq>(value: T) -> Option<T> {
+    Some(value).filter(|value| *value != T::default())
+}
+
+fn timestamp(
+    field: &'static str,
+    timestamp: Option<prost_types::Timestamp>,
+) -> Result<Option<DateTime<Utc>>, ConversionError> {
+    timestamp
+        .map(|timestamp| {
+            DateTime::from_timestamp(timestamp.seconds, timestamp.nanos.max(0) as u32).ok_or(
+                ConversionError::InvalidTimestamp {
+                    field,
+                    seconds: timestamp.seconds,
+                },
+            )
+        })
+        .transpose()
+}
+
+/// Converts an enum where `0` means IGDB didn't send one
+fn optional_enum<T: TryFrom<i32, Error = ConversionError>>(
+    value: i32,
+) -> Result<Option<T>, ConversionError> {
+    non_zero(value).map(T::try_from).transpose()
+}
+
+fn convert_all<P, T: TryFrom<P, Error = ConversionError>>(
+    values: Vec<P>,
+) -> Result<Vec<T>, ConversionError> {
+    values.into_iter().map(T::try_from).collect()
+}
+
+impl TryFrom<api::Game> for Game {
+    type Error = ConversionError;
+
+    fn try_from(game: api::Game) -> Result<Self, Self::Error> {
+        Ok(Game {
+            id: game.id,
+            name: text(game.name),
+            slug: text(game.slug),
+            summary: text(game.summary),
+            storyline: text(game.storyline),
+            url: text(game.url),
+            category: GameCategory::try_from(game.category)?,
+            status: optional_enum(game.status)?,
+            first_release_date: timestamp("first_release_date", game.first_release_date)?,
+            rating: non_zero(game.rating),
+            aggregated_rating: non_zero(game.aggregated_rating),
+            total_rating: non_zero(game.total_rating),
+            platforms: convert_all(game.platforms)?,
+            release_dates: convert_all(game.release_dates)?,
+            involved_companies: convert_all(game.involved_companies)?,
+        })
+    }
+}
+
+impl TryFrom<api::Platform> for Platform {
+    type Error = ConversionError;
+
+    fn try_from(platform: api::Platform) -> Result<Self, Self::Error> {
+        Ok(Platform {
+            id: platform.id,
+            name: text(platform.name),
+            abbreviation: text(platform.abbreviation),
+            slug: text(platform.slug),
+            category: optional_enum(platform.category)?,
+            generation: non_zero(platform.generation).map(|generation| generation as u32),
+        })
+    }
+}
+
+impl TryFrom<api::Company> for Company {
+    type Error = ConversionError;
+
+    fn try_from(company: api::Company) -> Result<Self, Self::Error> {
+        Ok(Company {
+            id: company.id,
+            name: text(company.name),
+            slug: text(company.slug),
+            description: text(company.description),
+            country: non_zero(company.country).map(|country| country as u16),
+            start_date: timestamp("start_date", company.start_date)?,
+            url: text(company.url),
+        })
+    }
+}
+
+impl TryFrom<api::InvolvedCompany> for InvolvedCompany {
+    type Error = ConversionError;
+
+    fn try_from(involved_company: api::InvolvedCompany) -> Result<Self, Self::Error> {
+        Ok(InvolvedCompany {
+            id: involved_company.id,
+            company: involved_company
+                .company
+                .map(Company::try_from)
+                .transpose()?,
+            developer: involved_company.developer,
+            publisher: involved_company.publisher,
+            porting: involved_company.porting,
+            supporting: involved_company.supporting,
+        })
+    }
+}
+
+impl TryFrom<api::ReleaseDate> for ReleaseDate {
+    type Error = ConversionError;
+
+    fn try_from(release_date: api::ReleaseDate) -> Result<Self, Self::Error> {
+        let category = api::DateFormatChangeDateCategoryEnum::try_from(release_date.category)
+            .map_err(|_| ConversionError::UnknownEnumValue {
+                name: "DatePrecision",
+                value: release_date.category,
+            })?;
+
+        Ok(ReleaseDate {
+            id: release_date.id,
+            region: optional_enum(release_date.region)?,
+            platform: release_date.platform.map(Platform::try_from).transpose()?,
+            date: timestamp("date", release_date.date)?,
+            precision: category.into(),
+            human: text(release_date.human),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::api;
+    use crate::errors::ConversionError;
+    use crate::mock_server::final_fantasy_vii;
+    use crate::release_dates::DatePrecision;
+
+    use super::{Game, GameCategory, GameStatus, PlatformCategory, Region};
+
+    #[test]
+    fn converts_a_game() {
+        let mut raw = final_fantasy_vii();
+        raw.category = api::GameCategoryEnum::Remake as i32;
+        raw.status = api::GameStatusEnum::EarlyAccess as i32;
+        raw.rating = 91.5;
+        raw.first_release_date = Some(prost_types::Timestamp {
+            seconds: 1586476800,
+            nanos: 0,
+        });
+        raw.platforms = vec![api::Platform {
+            id: 48,
+            name: "PlayStation 4".to_owned(),
+            category: api::PlatformCategoryEnum::Console as i32,
+            generation: 8,
+            ..Default::default()
+        }];
+        raw.release_dates = vec![api::ReleaseDate {
+            id: 7,
+            region: api::RegionRegionEnum::Japan as i32,
+            category: api::DateFormatChangeDateCategoryEnum::Yyyyq2 as i32,
+            human: "2020-Q2".to_owned(),
+            ..Default::default()
+        }];
+
+        let game = Game::try_from(raw).unwrap();
+
+        assert_eq!(Some("Final Fantasy VII".to_owned()), game.name);
+        assert_eq!(GameCategory::Remake, game.category);
+        assert_eq!(Some(GameStatus::EarlyAccess), game.status);
+        assert_eq!(Some(91.5), game.rating);
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2020, 4, 10, 0, 0, 0).unwrap()),
+            game.first_release_date
+        );
+
+        let platform = &game.platforms[0];
+        assert_eq!(Some(PlatformCategory::Console), platform.category);
+        assert_eq!(Some(8), platform.generation);
+        assert_eq!(None, platform.abbreviation);
+
+        let release_date = &game.release_dates[0];
+        assert_eq!(Some(Region::Japan), release_date.region);
+        assert_eq!(DatePrecision::Quarter(2), release_date.precision);
+        assert_eq!(Some("2020-Q2".to_owned()), release_date.human);
+    }
+
+    #[test]
+    fn missing_fields_are_none() {
+        let game = Game::try_from(api::Game {
+            id: 1,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(None, game.name);
+        assert_eq!(None, game.status);
+        assert_eq!(None, game.rating);
+        assert_eq!(None, game.first_release_date);
+        assert_eq!(GameCategory::MainGame, game.category);
+    }
+
+    #[test]
+    fn released_status_is_none() {
+        let game = Game::try_from(api::Game {
+            status: api::GameStatusEnum::Released as i32,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(0, api::GameStatusEnum::Released as i32);
+        assert_eq!(None, game.status);
+    }
+
+    #[test]
+    fn unknown_enum_values_are_errors() {
+        let raw = api::Game {
+            category: 99,
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(ConversionError::UnknownEnumValue {
+                name: "GameCategory",
+                value: 99
+            }),
+            Game::try_from(raw)
+        );
+
+        let raw = api::Game {
+            release_dates: vec![api::ReleaseDate {
+                region: 42,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(ConversionError::UnknownEnumValue {
+                name: "Region",
+                value: 42
+            }),
+            Game::try_from(raw)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt::Display;
+
+use prost::Message;
+use serde::Deserialize;
+
+use crate::api::MultiQueryResultArray;
+use crate::client::{IGDBClient, Transport};
+use crate::endpoint::Endpoint;
+use crate::errors::IGDBClientError;
+
+struct SubQuery {
+    endpoint: String,
+    name: String,
+    query: String,
+}
+
+impl Display for SubQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.name.replace('\\', "\\\\").replace('"', "\\\"");
+        write!(
+            f,
+            "query {} \"{}\" {{ {} }};",
+            self.endpoint, name, self.query
+        )
+    }
+}
+
+/// Most sub-queries IGDB accepts in a single multiquery request
+pub const MAX_SUB_QUERIES: usize = 10;
+
+/// Several named queries sent to IGDB in a single request, at most [`MAX_SUB_QUERIES`] of them
+#[derive(Default)]
+pub struct MultiQuery {
+    queries: Vec<SubQuery>,
+}
+
+impl MultiQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a query against the endpoint for `E`, where `query` is the output of
+    /// [`crate::query::QueryBuilder::build`]
+    pub fn query<E: Endpoint>(mut self, name: &str, query: String) -> Self {
+        self.queries.push(SubQuery {
+            endpoint: E::PATH.to_owned(),
+            name: name.to_owned(),
+            query,
+        });
+        self
+    }
+
+    /// Adds a query that only counts the matches against the endpoint for `E`
+    pub fn count<E: Endpoint>(mut self, name: &str, query: String) -> Self {
+        self.queries.push(SubQuery {
+            endpoint: format!("{}/count", E::PATH),
+            name: name.to_owned(),
+            query,
+        });
+        self
+    }
+
+    pub fn build(&self) -> String {
+        self.queries
+            .iter()
+            .map(|query| query.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Items of a sub-query, left undecoded until the caller says what type they are
+enum Items {
+    Protobuf(Vec<Vec<u8>>),
+    Json(Vec<serde_json::Value>),
+}
+
+struct NamedResult {
+    name: String,
+    items: Items,
+    count: i64,
+}
+
+/// How the JSON multiquery endpoint sends each sub-query's result, since it can't be mapped onto the protobuf
+/// `MultiQueryResult` and its encoded items
+#[derive(Deserialize)]
+struct JsonMultiQueryResult {
+    name: String,
+    #[serde(default)]
+    result: Vec<serde_json::Value>,
+    #[serde(default)]
+    count: i64,
+}
+
+/// Results of a [`MultiQuery`], looked up by the name each sub-query was given
+pub struct MultiQueryResults {
+    results: Vec<NamedResult>,
+}
+
+impl MultiQueryResults {
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.results.iter().map(|result| result.name.as_str())
+    }
+
+    /// Decodes the results of the sub-query called `name` into the item type of `E`
+    pub fn items<E: Endpoint>(&self, name: &str) -> Result<Vec<E::Item>, IGDBClientError> {
+        match &self.find(name)?.items {
+            Items::Protobuf(items) => items
+                .iter()
+                .map(|bytes| E::Item::decode(bytes.as_slice()).map_err(IGDBClientError::from))
+                .collect(),
+            Items::Json(items) => items
+                .iter()
+                .map(|item| E::Item::deserialize(item).map_err(IGDBClientError::from))
+                .collect(),
+        }
+    }
+
+    /// Count returned by a sub-query added with [`MultiQuery::count`]
+    pub fn count(&self, name: &str) -> Result<i64, IGDBClientError> {
+        Ok(self.find(name)?.count)
+    }
+
+    fn find(&self, name: &str) -> Result<&NamedResult, IGDBClientError> {
+        self.results
+            .iter()
+            .find(|result| result.name == name)
+            .ok_or_else(|| IGDBClientError::MissingMultiQueryResultError {
+                name: name.to_owned(),
+            })
+    }
+}
+
+impl IGDBClient {
+    /// Sends `query`, failing with [`IGDBClientError::BadQueryError`] without a request if it has more sub-queries
+    /// than IGDB accepts
+    pub async fn multiquery(
+        &self,
+        query: MultiQuery,
+    ) -> Result<MultiQueryResults, IGDBClientError> {
+        if query.queries.len() > MAX_SUB_QUERIES {
+            return Err(IGDBClientError::BadQueryError {
+                query: query.build(),
+                endpoint: "multiquery".to_owned(),
+                title: format!(
+                    "{} sub-queries, but IGDB accepts at most {}",
+                    query.queries.len(),
+                    MAX_SUB_QUERIES
+                ),
+                cause: None,
+            });
+        }
+
+        let results = match self.transport() {
+            Transport::Protobuf => self
+                .query_endpoint::<MultiQueryResultArray>("multiquery".to_owned(), query.build())
+                .await?
+                .result
+                .into_iter()
+                .map(|result| NamedResult {
+                    name: result.name,
+                    items: Items::Protobuf(result.results),
+                    count: result.count,
+                })
+                .collect(),
+            Transport::Json => self
+                .query_json::<Vec<JsonMultiQueryResult>>("multiquery", query.build())
+                .await?
+                .into_iter()
+                .map(|result| NamedResult {
+                    name: result.name,
+                    items: Items::Json(result.result),
+                    count: result.count,
+                })
+                .collect(),
+        };
+
+        Ok(MultiQueryResults { results })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use wiremock::matchers::{body_string, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::api::{
+        Cover, CoverResult, GameResult, MultiQueryResult, MultiQueryResultArray, ReleaseDateResult,
+    };
+    use crate::errors::IGDBClientError;
+    use crate::mock_server::*;
+    use crate::query::Query;
+
+    use super::{MultiQuery, MAX_SUB_QUERIES};
+
+    fn game_details_query() -> MultiQuery {
+        MultiQuery::new()
+            .query::<GameResult>("Game", Query::where_("id = 427").build())
+            .query::<CoverResult>(
+                "Cover",
+                Query::select("image_id").where_("game = 427").build(),
+            )
+            .count::<ReleaseDateResult>("Release Dates", Query::where_("game = 427").build())
+    }
+
+    #[test]
+    fn builds_named_sub_queries() {
+        let query = game_details_query().build();
+
+        assert_eq!(
+            "query games \"Game\" { fields *; where id = 427; };\n\
+             query covers \"Cover\" { fields image_id; where game = 427; };\n\
+             query release_dates/count \"Release Dates\" { fields *; where game = 427; };",
+            query
+        );
+    }
+
+    #[test]
+    fn escapes_quotes_in_names() {
+        let query = MultiQuery::new()
+            .query::<GameResult>("\"Quoted\"", Query::select("name").build())
+            .build();
+
+        assert_eq!("query games \"\\\"Quoted\\\"\" { fields name; };", query);
+    }
+
+    #[tokio::test]
+    async fn decodes_each_named_result() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let cover = Cover {
+            id: 1,
+            image_id: "co1abc".to_owned(),
+            ..Default::default()
+        };
+        let fixture = MultiQueryResultArray {
+            result: vec![
+                MultiQueryResult {
+                    name: "Game".to_owned(),
+                    results: vec![final_fantasy_vii().encode_to_vec()],
+                    count: 0,
+                },
+                MultiQueryResult {
+                    name: "Cover".to_owned(),
+                    results: vec![cover.encode_to_vec()],
+                    count: 0,
+                },
+                MultiQueryResult {
+                    name: "Release Dates".to_owned(),
+                    results: vec![],
+                    count: 12,
+                },
+            ],
+        };
+
+        Mock::given(method("POST"))
+            .and(path("/v4/multiquery.pb"))
+            .and(body_string(game_details_query().build()))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(fixture.encode_to_vec()))
+            .mount(&server)
+            .await;
+
+        let json_fixture = serde_json::json!([
+            {"name": "Game", "result": [final_fantasy_vii()]},
+            {"name": "Cover", "result": [cover]},
+            {"name": "Release Dates", "count": 12},
+        ]);
+
+        Mock::given(method("POST"))
+            .and(path("/v4/multiquery"))
+            .and(body_string(game_details_query().build()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json_fixture))
+            .mount(&server)
+            .await;
+
+        for transport in TRANSPORTS {
+            let client = offline_client_with_transport(&server, transport);
+            let results = client.multiquery(game_details_query()).await.unwrap();
+
+            let games = results.items::<GameResult>("Game").unwrap();
+            assert_eq!("Final Fantasy VII", games[0].name);
+
+            let covers = results.items::<CoverResult>("Cover").unwrap();
+            assert_eq!("co1abc", covers[0].image_id);
+
+            assert_eq!(12, results.count("Release Dates").unwrap());
+            assert!(matches!(
+                results.count("Platforms"),
+                Err(IGDBClientError::MissingMultiQueryResultError { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn too_many_sub_queries_are_rejected_before_sending() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let query = (0..=MAX_SUB_QUERIES).fold(MultiQuery::new(), |query, i| {
+            query.query::<GameResult>(&i.to_string(), Query::where_("id = 427").build())
+        });
+
+        let client = offline_client(&server);
+        match client.multiquery(query).await {
+            Err(IGDBClientError::BadQueryError { endpoint, .. }) => {
+                assert_eq!("multiquery", endpoint)
+            }
+            other => panic!("expected a bad query error, got {:?}", other.err()),
+        }
+        assert_eq!(0, requests_to(&server, "/v4/multiquery.pb").await);
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt::Display;
+
+use futures::stream::{self, Stream, TryStreamExt};
+
+use crate::client::IGDBClient;
+use crate::endpoint::Endpoint;
+use crate::errors::IGDBClientError;
+use crate::query::{QueryBuilder, UnspecifiedOption};
+
+/// Largest `limit` IGDB accepts for a single request
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+impl IGDBClient {
+    /// Streams every item matching `query`, fetching `page_size` items at a time until IGDB runs out.
+    ///
+    /// The paginator owns `limit` and `offset`, so `query` must not set either. IGDB's order is only stable with a
+    /// sort, so pages are sorted by `id` unless `query` sets its own. Each page goes through the client's rate limiter
+    /// like any other request, and the stream ends after yielding the first error.
+    pub fn paginate<E, F, WC, Se, So, Ex>(
+        &self,
+        query: QueryBuilder<F, WC, UnspecifiedOption, UnspecifiedOption, Se, So, Ex>,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<E::Item, IGDBClientError>>
+    where
+        E: Endpoint,
+        F: Display,
+        WC: Display,
+        Se: Display,
+        So: Display,
+        Ex: Display,
+    {
+        let client = self.clone();
+        let base_query = if query.has_sort() {
+            query.build()
+        } else {
+            format!("{} sort id asc;", query.build())
+        };
+        let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+
+        stream::try_unfold(Some(0u32), move |offset| {
+            next_page::<E>(client.clone(), base_query.clone(), page_size, offset)
+        })
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+/// Fetches the page at `offset`, along with the offset of the page after it if there might be one
+async fn next_page<E: Endpoint>(
+    client: IGDBClient,
+    base_query: String,
+    page_size: u32,
+    offset: Option<u32>,
+) -> Result<Option<(Vec<E::Item>, Option<u32>)>, IGDBClientError> {
+    let offset = match offset {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+
+    let page_query = format!("{} limit {}; offset {};", base_query, page_size, offset);
+    let page = client.query::<E>(page_query).await?;
+    let next_offset = if page.len() < page_size as usize {
+        None
+    } else {
+        Some(offset + page_size)
+    };
+
+    Ok(Some((page, next_offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::matchers::{body_string, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::api::{ReleaseDate, ReleaseDateResult};
+    use crate::client::Transport;
+    use crate::mock_server::*;
+    use crate::query::{Query, SortDirection};
+
+    async fn mock_page(server: &MockServer, offset: u32, ids: &[u64]) {
+        let fixture = ReleaseDateResult {
+            releasedates: ids
+                .iter()
+                .map(|&id| ReleaseDate {
+                    id,
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        for transport in TRANSPORTS {
+            Mock::given(method("POST"))
+                .and(path(endpoint_path("release_dates", transport)))
+                .and(body_string(format!(
+                    "fields *; where platform = 48; sort id asc; limit 2; offset {};",
+                    offset
+                )))
+                .respond_with(fixture_response(&fixture, transport))
+                .mount(server)
+                .await;
+        }
+    }
+
+    async fn collect_ids(server: &MockServer, transport: Transport) -> Vec<u64> {
+        let client = offline_client_with_transport(server, transport);
+        client
+            .paginate::<ReleaseDateResult, _, _, _, _, _>(Query::where_("platform = 48"), 2)
+            .map_ok(|release_date| release_date.id)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn walks_pages_until_a_short_page() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        mock_page(&server, 0, &[1, 2]).await;
+        mock_page(&server, 2, &[3, 4]).await;
+        mock_page(&server, 4, &[5]).await;
+
+        for transport in TRANSPORTS {
+            assert_eq!(vec![1, 2, 3, 4, 5], collect_ids(&server, transport).await);
+            let path = endpoint_path("release_dates", transport);
+            assert_eq!(3, requests_to(&server, &path).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_on_an_empty_page() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        mock_page(&server, 0, &[1, 2]).await;
+        mock_page(&server, 2, &[]).await;
+
+        for transport in TRANSPORTS {
+            assert_eq!(vec![1, 2], collect_ids(&server, transport).await);
+            let path = endpoint_path("release_dates", transport);
+            assert_eq!(2, requests_to(&server, &path).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_after_an_error() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+        mock_page(&server, 0, &[1, 2]).await;
+
+        Mock::given(method("POST"))
+            .and(path("/v4/release_dates.pb"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        let results: Vec<_> = futures::StreamExt::collect(
+            client.paginate::<ReleaseDateResult, _, _, _, _, _>(Query::where_("platform = 48"), 2),
+        )
+        .await;
+
+        assert_eq!(3, results.len());
+        assert!(results[2].is_err());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_query_sort() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let fixture = ReleaseDateResult {
+            releasedates: vec![ReleaseDate {
+                id: 1,
+                ..Default::default()
+            }],
+        };
+        Mock::given(method("POST"))
+            .and(path("/v4/release_dates.pb"))
+            .and(body_string("fields *; sort date desc; limit 2; offset 0;"))
+            .respond_with(fixture_response(&fixture, Transport::Protobuf))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        let ids: Vec<u64> = client
+            .paginate::<ReleaseDateResult, _, _, _, _, _>(
+                Query::sort("date", SortDirection::Descending),
+                2,
+            )
+            .map_ok(|release_date| release_date.id)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(vec![1], ids);
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt::Display;
+
+/// Quotes a string for use in an Apicalypse query, escaping anything that would end the string early
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Right hand side of a comparison, rendered the way Apicalypse expects it
+pub enum Value {
+    Number(String),
+    Text(String),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Text(s) => write!(f, "{}", quote(s)),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+macro_rules! number_values {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::Number(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+number_values!(i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64);
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+/// A condition for a `where` clause. Combining predicates with [`Predicate::and`] and [`Predicate::or`] adds
+/// parentheses wherever `&` and `|` are mixed, so the query means what the code says.
+pub enum Predicate {
+    Clause(String),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Matches nothing. Apicalypse has no literal for false, so this relies on every IGDB entity having an id.
+    pub fn never() -> Predicate {
+        Predicate::Clause("id = null".to_owned())
+    }
+
+    /// Matches everything, see [`Predicate::never`]
+    pub fn always() -> Predicate {
+        Predicate::Clause("id != null".to_owned())
+    }
+
+    pub fn and(self, other: impl Into<Predicate>) -> Predicate {
+        match self {
+            Predicate::And(mut predicates) => {
+                predicates.push(other.into());
+                Predicate::And(predicates)
+            }
+            predicate => Predicate::And(vec![predicate, other.into()]),
+        }
+    }
+
+    pub fn or(self, other: impl Into<Predicate>) -> Predicate {
+        match self {
+            Predicate::Or(mut predicates) => {
+                predicates.push(other.into());
+                Predicate::Or(predicates)
+            }
+            predicate => Predicate::Or(vec![predicate, other.into()]),
+        }
+    }
+
+    fn write_joined(
+        f: &mut std::fmt::Formatter<'_>,
+        predicates: &[Predicate],
+        separator: &str,
+    ) -> std::fmt::Result {
+        for (i, predicate) in predicates.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", separator)?;
+            }
+
+            match predicate {
+                Predicate::Clause(_) => write!(f, "{}", predicate)?,
+                _ => write!(f, "({})", predicate)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Predicate::Clause(clause) => write!(f, "{}", clause),
+            Predicate::And(predicates) => Self::write_joined(f, predicates, "&"),
+            Predicate::Or(predicates) => Self::write_joined(f, predicates, "|"),
+        }
+    }
+}
+
+/// Raw clauses are passed through untouched, so they are only safe for trusted input
+impl From<&str> for Predicate {
+    fn from(clause: &str) -> Self {
+        Predicate::Clause(clause.to_owned())
+    }
+}
+
+impl From<String> for Predicate {
+    fn from(clause: String) -> Self {
+        Predicate::Clause(clause)
+    }
+}
+
+pub struct Field {
+    name: String,
+    ignore_case: bool,
+}
+
+/// Starts a predicate on `name`, e.g. `field("id").eq(5)`
+pub fn field(name: &str) -> Field {
+    Field {
+        name: name.to_owned(),
+        ignore_case: false,
+    }
+}
+
+impl Field {
+    /// Makes the string matching predicates (`eq`, `ne`, `starts_with`, `ends_with`, `contains`) case-insensitive.
+    /// `~` only applies to strings, so comparisons with numbers and bools stay exact.
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+
+    fn clause(&self, operator: &str, value: impl Display) -> Predicate {
+        Predicate::Clause(format!("{} {} {}", self.name, operator, value))
+    }
+
+    fn list<V: Into<Value> + Clone>(values: &[V], open: char, close: char) -> String {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| value.clone().into().to_string())
+            .collect();
+        format!("{}{}{}", open, values.join(","), close)
+    }
+
+    fn match_operator(&self) -> &'static str {
+        if self.ignore_case {
+            "~"
+        } else {
+            "="
+        }
+    }
+
+    pub fn eq(&self, value: impl Into<Value>) -> Predicate {
+        match value.into() {
+            value @ Value::Text(_) => self.clause(self.match_operator(), value),
+            value => self.clause("=", value),
+        }
+    }
+
+    pub fn ne(&self, value: impl Into<Value>) -> Predicate {
+        match value.into() {
+            value @ Value::Text(_) if self.ignore_case => self.clause("!~", value),
+            value => self.clause("!=", value),
+        }
+    }
+
+    pub fn gt(&self, value: impl Into<Value>) -> Predicate {
+        self.clause(">", value.into())
+    }
+
+    pub fn ge(&self, value: impl Into<Value>) -> Predicate {
+        self.clause(">=", value.into())
+    }
+
+    pub fn lt(&self, value: impl Into<Value>) -> Predicate {
+        self.clause("<", value.into())
+    }
+
+    pub fn le(&self, value: impl Into<Value>) -> Predicate {
+        self.clause("<=", value.into())
+    }
+
+    /// Matches if the field equals (or, for arrays, contains) any of `values`, and nothing if `values` is empty
+    pub fn in_<V: Into<Value> + Clone>(&self, values: &[V]) -> Predicate {
+        if values.is_empty() {
+            return Predicate::never();
+        }
+        self.clause("=", Self::list(values, '(', ')'))
+    }
+
+    /// Matches if the field equals (or, for arrays, contains) none of `values`, and everything if `values` is empty
+    pub fn not_in<V: Into<Value> + Clone>(&self, values: &[V]) -> Predicate {
+        if values.is_empty() {
+            return Predicate::always();
+        }
+        self.clause("!=", Self::list(values, '(', ')'))
+    }
+
+    /// Matches array fields containing every one of `values`, and everything if `values` is empty
+    pub fn contains_all<V: Into<Value> + Clone>(&self, values: &[V]) -> Predicate {
+        if values.is_empty() {
+            return Predicate::always();
+        }
+        self.clause("=", Self::list(values, '[', ']'))
+    }
+
+    /// Matches array fields containing exactly `values` and nothing else, so empty `values` matches empty arrays
+    pub fn contains_exactly<V: Into<Value> + Clone>(&self, values: &[V]) -> Predicate {
+        if values.is_empty() {
+            return self.is_null();
+        }
+        self.clause("=", Self::list(values, '{', '}'))
+    }
+
+    pub fn is_null(&self) -> Predicate {
+        self.clause("=", "null")
+    }
+
+    pub fn is_not_null(&self) -> Predicate {
+        self.clause("!=", "null")
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> Predicate {
+        self.clause(self.match_operator(), format!("{}*", quote(prefix)))
+    }
+
+    pub fn ends_with(&self, suffix: &str) -> Predicate {
+        self.clause(self.match_operator(), format!("*{}", quote(suffix)))
+    }
+
+    pub fn contains(&self, text: &str) -> Predicate {
+        self.clause(self.match_operator(), format!("*{}*", quote(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field, quote, Predicate};
+
+    #[test]
+    fn quotes_and_escapes_strings() {
+        assert_eq!(r#""Mario""#, quote("Mario"));
+        assert_eq!(r#""Say \"Hi\"""#, quote(r#"Say "Hi""#));
+        assert_eq!(r#""C:\\Games""#, quote(r"C:\Games"));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!("id = 5", field("id").eq(5).to_string());
+        assert_eq!("id != 5", field("id").ne(5).to_string());
+        assert_eq!(
+            "date > 1700000000",
+            field("date").gt(1700000000i64).to_string()
+        );
+        assert_eq!("rating >= 80.5", field("rating").ge(80.5).to_string());
+        assert_eq!("rating < 10", field("rating").lt(10).to_string());
+        assert_eq!("rating <= 10", field("rating").le(10).to_string());
+        assert_eq!("dlc = true", field("dlc").eq(true).to_string());
+        assert_eq!(r#"name = "Mario""#, field("name").eq("Mario").to_string());
+    }
+
+    #[test]
+    fn string_values_are_escaped() {
+        let predicate = field("name").eq(r#"Halo"; fields *; where id = 1"#);
+        assert_eq!(
+            r#"name = "Halo\"; fields *; where id = 1""#,
+            predicate.to_string()
+        );
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            "platforms = (48,49)",
+            field("platforms").in_(&[48, 49]).to_string()
+        );
+        assert_eq!(
+            "platforms != (48,49)",
+            field("platforms").not_in(&[48, 49]).to_string()
+        );
+        assert_eq!(
+            "genres = [5,12]",
+            field("genres").contains_all(&[5, 12]).to_string()
+        );
+        assert_eq!(
+            "genres = {5,12}",
+            field("genres").contains_exactly(&[5, 12]).to_string()
+        );
+        assert_eq!(
+            r#"slug = ("halo","halo-2")"#,
+            field("slug").in_(&["halo", "halo-2"]).to_string()
+        );
+    }
+
+    #[test]
+    fn empty_lists_are_never_rendered() {
+        let none: [u64; 0] = [];
+        assert_eq!("id = null", field("platforms").in_(&none).to_string());
+        assert_eq!("id != null", field("platforms").not_in(&none).to_string());
+        assert_eq!(
+            "id != null",
+            field("genres").contains_all(&none).to_string()
+        );
+        assert_eq!(
+            "genres = null",
+            field("genres").contains_exactly(&none).to_string()
+        );
+    }
+
+    #[test]
+    fn ignore_case_only_applies_to_strings() {
+        assert_eq!("id = 5", field("id").ignore_case().eq(5).to_string());
+        assert_eq!("id != 5", field("id").ignore_case().ne(5).to_string());
+        assert_eq!(
+            "dlc = true",
+            field("dlc").ignore_case().eq(true).to_string()
+        );
+    }
+
+    #[test]
+    fn nulls() {
+        assert_eq!("cover = null", field("cover").is_null().to_string());
+        assert_eq!("cover != null", field("cover").is_not_null().to_string());
+    }
+
+    #[test]
+    fn string_matching() {
+        assert_eq!(
+            r#"name = "Zelda"*"#,
+            field("name").starts_with("Zelda").to_string()
+        );
+        assert_eq!(
+            r#"name = *"Zelda""#,
+            field("name").ends_with("Zelda").to_string()
+        );
+        assert_eq!(
+            r#"name = *"Zelda"*"#,
+            field("name").contains("Zelda").to_string()
+        );
+        assert_eq!(
+            r#"name ~ "zelda""#,
+            field("name").ignore_case().eq("zelda").to_string()
+        );
+        assert_eq!(
+            r#"name ~ "zelda"*"#,
+            field("name").ignore_case().starts_with("zelda").to_string()
+        );
+        assert_eq!(
+            r#"name !~ "zelda""#,
+            field("name").ignore_case().ne("zelda").to_string()
+        );
+    }
+
+    #[test]
+    fn same_operator_is_not_grouped() {
+        let predicate = field("a").eq(1).and(field("b").eq(2)).and(field("c").eq(3));
+        assert_eq!("a = 1 & b = 2 & c = 3", predicate.to_string());
+    }
+
+    #[test]
+    fn mixed_operators_are_grouped() {
+        let predicate = field("a").eq(1).and(field("b").eq(2)).or(field("c").eq(3));
+        assert_eq!("(a = 1 & b = 2) | c = 3", predicate.to_string());
+
+        let predicate = field("a").eq(1).and(field("b").eq(2).or(field("c").eq(3)));
+        assert_eq!("a = 1 & (b = 2 | c = 3)", predicate.to_string());
+    }
+
+    #[test]
+    fn raw_clauses_pass_through() {
+        let predicate = Predicate::from("id = 5").and("value = 42");
+        assert_eq!("id = 5 & value = 42", predicate.to_string());
+    }
+}
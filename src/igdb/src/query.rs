@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::predicate::{quote, Predicate};
+
 pub struct UnspecifiedOption;
 impl Display for UnspecifiedOption {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,16 +25,51 @@ impl Display for Fields {
     }
 }
 
-pub struct WhereClause(String);
-impl WhereClause {
-    fn new(clause: &str) -> Self {
-        WhereClause(format!("where {}", clause))
+pub struct Exclude(Vec<String>);
+
+impl Display for Exclude {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let joined = self.0.join(", ");
+        write!(f, "exclude {}; ", joined)
+    }
+}
+
+/// Dotted path to a field, e.g. `cover.image_id`. The types in [`crate::fields`] build these from the field names in
+/// the proto, so a misspelled or non-existent field is a compile error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPath(String);
+
+impl FieldPath {
+    pub(crate) fn root() -> Self {
+        FieldPath(String::new())
+    }
+
+    pub(crate) fn join(&self, field: &str) -> Self {
+        if self.0.is_empty() {
+            FieldPath(field.to_owned())
+        } else {
+            FieldPath(format!("{}.{}", self.0, field))
+        }
+    }
+}
+
+impl AsRef<str> for FieldPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
+pub struct WhereClause(Predicate);
+
 impl Display for WhereClause {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}; ", self.0)
+        write!(f, "where {}; ", self.0)
     }
 }
 
@@ -63,7 +100,7 @@ impl Display for Offset {
 pub struct Search(String);
 impl Search {
     fn new(query: &str) -> Self {
-        Search(format!("search \"{}\"; ", query))
+        Search(format!("search {}; ", quote(query)))
     }
 }
 impl Display for Search {
@@ -89,13 +126,13 @@ impl Display for Sort {
     }
 }
 
-pub struct Query(String);
+pub struct Query;
 
 type U = UnspecifiedOption;
 
 impl Query {
-    pub fn select(field: &str) -> QueryBuilder<Fields, U, U, U, U, U> {
-        let fields = [field.to_owned()].to_vec();
+    pub fn select(field: impl AsRef<str>) -> QueryBuilder<Fields, U, U, U, U, U, U> {
+        let fields = [field.as_ref().to_owned()].to_vec();
 
         QueryBuilder {
             fields: Fields(fields),
@@ -104,10 +141,11 @@ impl Query {
             offset: UnspecifiedOption,
             search: UnspecifiedOption,
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
         }
     }
 
-    pub fn select_multiple(fields: &[String]) -> QueryBuilder<Fields, U, U, U, U, U> {
+    pub fn select_multiple(fields: &[String]) -> QueryBuilder<Fields, U, U, U, U, U, U> {
         QueryBuilder {
             fields: Fields(fields.to_vec()),
             where_clause: UnspecifiedOption,
@@ -115,21 +153,25 @@ impl Query {
             offset: UnspecifiedOption,
             search: UnspecifiedOption,
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
         }
     }
 
-    pub fn where_(clause: &str) -> QueryBuilder<AllFields, WhereClause, U, U, U, U> {
+    pub fn where_(
+        clause: impl Into<Predicate>,
+    ) -> QueryBuilder<AllFields, WhereClause, U, U, U, U, U> {
         QueryBuilder {
             fields: AllFields,
-            where_clause: WhereClause::new(clause),
+            where_clause: WhereClause(clause.into()),
             limit: UnspecifiedOption,
             offset: UnspecifiedOption,
             search: UnspecifiedOption,
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
         }
     }
 
-    pub fn search(query: &str) -> QueryBuilder<AllFields, U, U, U, Search, U> {
+    pub fn search(query: &str) -> QueryBuilder<AllFields, U, U, U, Search, U, U> {
         QueryBuilder {
             fields: AllFields,
             where_clause: UnspecifiedOption,
@@ -137,10 +179,11 @@ impl Query {
             offset: UnspecifiedOption,
             search: Search::new(query),
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
         }
     }
 
-    pub fn limit(limit: u32) -> QueryBuilder<AllFields, U, Limit, U, U, U> {
+    pub fn limit(limit: u32) -> QueryBuilder<AllFields, U, Limit, U, U, U, U> {
         QueryBuilder {
             fields: AllFields,
             where_clause: UnspecifiedOption,
@@ -148,10 +191,11 @@ impl Query {
             offset: UnspecifiedOption,
             search: UnspecifiedOption,
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
         }
     }
 
-    pub fn offset(offset: u32) -> QueryBuilder<AllFields, U, U, Offset, U, U> {
+    pub fn offset(offset: u32) -> QueryBuilder<AllFields, U, U, Offset, U, U, U> {
         QueryBuilder {
             fields: AllFields,
             where_clause: UnspecifiedOption,
@@ -159,13 +203,26 @@ impl Query {
             offset: Offset::new(offset),
             search: UnspecifiedOption,
             sort: UnspecifiedOption,
+            exclude: UnspecifiedOption,
+        }
+    }
+
+    pub fn exclude(field: impl AsRef<str>) -> QueryBuilder<AllFields, U, U, U, U, U, Exclude> {
+        QueryBuilder {
+            fields: AllFields,
+            where_clause: UnspecifiedOption,
+            limit: UnspecifiedOption,
+            offset: UnspecifiedOption,
+            search: UnspecifiedOption,
+            sort: UnspecifiedOption,
+            exclude: Exclude(vec![field.as_ref().to_owned()]),
         }
     }
 
     pub fn sort(
         field: &str,
         direction: SortDirection,
-    ) -> QueryBuilder<AllFields, U, U, U, U, Sort> {
+    ) -> QueryBuilder<AllFields, U, U, U, U, Sort, U> {
         QueryBuilder {
             fields: AllFields,
             where_clause: UnspecifiedOption,
@@ -173,22 +230,24 @@ impl Query {
             offset: UnspecifiedOption,
             search: UnspecifiedOption,
             sort: Sort(field.to_owned(), direction),
+            exclude: UnspecifiedOption,
         }
     }
 }
 
-pub struct QueryBuilder<F, WC, L, O, Se, So> {
+pub struct QueryBuilder<F, WC, L, O, Se, So, Ex> {
     fields: F,
     where_clause: WC,
     limit: L,
     offset: O,
     search: Se,
     sort: So,
+    exclude: Ex,
 }
 
-impl<WC, L, O, Se, So> QueryBuilder<AllFields, WC, L, O, Se, So> {
-    pub fn select(self, field: &str) -> QueryBuilder<Fields, WC, L, O, Se, So> {
-        let fields = [field.to_owned()];
+impl<WC, L, O, Se, So, Ex> QueryBuilder<AllFields, WC, L, O, Se, So, Ex> {
+    pub fn select(self, field: impl AsRef<str>) -> QueryBuilder<Fields, WC, L, O, Se, So, Ex> {
+        let fields = [field.as_ref().to_owned()];
 
         QueryBuilder {
             fields: Fields(fields.to_vec()),
@@ -197,10 +256,11 @@ impl<WC, L, O, Se, So> QueryBuilder<AllFields, WC, L, O, Se, So> {
             offset: self.offset,
             search: self.search,
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 
-    pub fn select_multiple(self, fields: &[String]) -> QueryBuilder<Fields, WC, L, O, Se, So> {
+    pub fn select_multiple(self, fields: &[String]) -> QueryBuilder<Fields, WC, L, O, Se, So, Ex> {
         QueryBuilder {
             fields: Fields(fields.to_vec()),
             where_clause: self.where_clause,
@@ -208,57 +268,62 @@ impl<WC, L, O, Se, So> QueryBuilder<AllFields, WC, L, O, Se, So> {
             offset: self.offset,
             search: self.search,
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 }
 
-impl<WC, L, O, Se, So> QueryBuilder<Fields, WC, L, O, Se, So> {
-    pub fn select(mut self, field: &str) -> QueryBuilder<Fields, WC, L, O, Se, So> {
-        self.fields.0.push(field.to_owned());
+impl<WC, L, O, Se, So, Ex> QueryBuilder<Fields, WC, L, O, Se, So, Ex> {
+    pub fn select(mut self, field: impl AsRef<str>) -> QueryBuilder<Fields, WC, L, O, Se, So, Ex> {
+        self.fields.0.push(field.as_ref().to_owned());
         self
     }
 
-    pub fn select_multiple(mut self, fields: &[String]) -> QueryBuilder<Fields, WC, L, O, Se, So> {
+    pub fn select_multiple(
+        mut self,
+        fields: &[String],
+    ) -> QueryBuilder<Fields, WC, L, O, Se, So, Ex> {
         self.fields.0.extend(fields.to_vec());
         self
     }
 }
 
-impl<F, L, O, Se, So> QueryBuilder<F, UnspecifiedOption, L, O, Se, So> {
-    pub fn where_(self, clause: &str) -> QueryBuilder<F, WhereClause, L, O, Se, So> {
+impl<F, L, O, Se, So, Ex> QueryBuilder<F, UnspecifiedOption, L, O, Se, So, Ex> {
+    pub fn where_(
+        self,
+        clause: impl Into<Predicate>,
+    ) -> QueryBuilder<F, WhereClause, L, O, Se, So, Ex> {
         QueryBuilder {
             fields: self.fields,
-            where_clause: WhereClause::new(clause),
+            where_clause: WhereClause(clause.into()),
             limit: self.limit,
             offset: self.offset,
             search: self.search,
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 }
 
-impl<F, L, O, Se, So> QueryBuilder<F, WhereClause, L, O, Se, So> {
-    pub fn and(mut self, clause: &str) -> Self {
-        let existing = self.where_clause.0;
-        let updated = format!("{} & {}", existing, clause);
-        self.where_clause = WhereClause(updated);
+impl<F, L, O, Se, So, Ex> QueryBuilder<F, WhereClause, L, O, Se, So, Ex> {
+    pub fn and(mut self, clause: impl Into<Predicate>) -> Self {
+        self.where_clause = WhereClause(self.where_clause.0.and(clause));
         self
     }
 
-    pub fn or(mut self, clause: &str) -> Self {
-        let existing = self.where_clause.0;
-        let updated = format!("{} | {}", existing, clause);
-        self.where_clause = WhereClause(updated);
+    /// Everything already in the where clause is grouped together, so `where_(a).and(b).or(c)` is `(a & b) | c`
+    pub fn or(mut self, clause: impl Into<Predicate>) -> Self {
+        self.where_clause = WhereClause(self.where_clause.0.or(clause));
         self
     }
 
-    pub fn where_(self, clause: &str) -> Self {
+    pub fn where_(self, clause: impl Into<Predicate>) -> Self {
         self.and(clause)
     }
 }
 
-impl<F, WC, O, Se, So> QueryBuilder<F, WC, UnspecifiedOption, O, Se, So> {
-    pub fn limit(self, limit: u32) -> QueryBuilder<F, WC, Limit, O, Se, So> {
+impl<F, WC, O, Se, So, Ex> QueryBuilder<F, WC, UnspecifiedOption, O, Se, So, Ex> {
+    pub fn limit(self, limit: u32) -> QueryBuilder<F, WC, Limit, O, Se, So, Ex> {
         QueryBuilder {
             fields: self.fields,
             where_clause: self.where_clause,
@@ -266,12 +331,13 @@ impl<F, WC, O, Se, So> QueryBuilder<F, WC, UnspecifiedOption, O, Se, So> {
             offset: self.offset,
             search: self.search,
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 }
 
-impl<F, WC, L, Se, So> QueryBuilder<F, WC, L, UnspecifiedOption, Se, So> {
-    pub fn offset(self, offset: u32) -> QueryBuilder<F, WC, L, Offset, Se, So> {
+impl<F, WC, L, Se, So, Ex> QueryBuilder<F, WC, L, UnspecifiedOption, Se, So, Ex> {
+    pub fn offset(self, offset: u32) -> QueryBuilder<F, WC, L, Offset, Se, So, Ex> {
         QueryBuilder {
             fields: self.fields,
             where_clause: self.where_clause,
@@ -279,12 +345,13 @@ impl<F, WC, L, Se, So> QueryBuilder<F, WC, L, UnspecifiedOption, Se, So> {
             offset: Offset::new(offset),
             search: self.search,
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 }
 
-impl<F, WC, L, O, So> QueryBuilder<F, WC, L, O, UnspecifiedOption, So> {
-    pub fn search(self, query: &str) -> QueryBuilder<F, WC, L, O, Search, So> {
+impl<F, WC, L, O, So, Ex> QueryBuilder<F, WC, L, O, UnspecifiedOption, So, Ex> {
+    pub fn search(self, query: &str) -> QueryBuilder<F, WC, L, O, Search, So, Ex> {
         QueryBuilder {
             fields: self.fields,
             where_clause: self.where_clause,
@@ -292,16 +359,17 @@ impl<F, WC, L, O, So> QueryBuilder<F, WC, L, O, UnspecifiedOption, So> {
             offset: self.offset,
             search: Search::new(query),
             sort: self.sort,
+            exclude: self.exclude,
         }
     }
 }
 
-impl<F, WC, L, O, Se> QueryBuilder<F, WC, L, O, Se, UnspecifiedOption> {
+impl<F, WC, L, O, Se, Ex> QueryBuilder<F, WC, L, O, Se, UnspecifiedOption, Ex> {
     pub fn sort(
         self,
         field: &str,
         direction: SortDirection,
-    ) -> QueryBuilder<F, WC, L, O, Se, Sort> {
+    ) -> QueryBuilder<F, WC, L, O, Se, Sort, Ex> {
         QueryBuilder {
             fields: self.fields,
             where_clause: self.where_clause,
@@ -309,11 +377,42 @@ impl<F, WC, L, O, Se> QueryBuilder<F, WC, L, O, Se, UnspecifiedOption> {
             offset: self.offset,
             search: self.search,
             sort: Sort(field.to_owned(), direction),
+            exclude: self.exclude,
         }
     }
 }
 
-impl<F, WC, L, O, Se, So> QueryBuilder<F, WC, L, O, Se, So>
+impl<F, WC, L, O, Se, So> QueryBuilder<F, WC, L, O, Se, So, UnspecifiedOption> {
+    pub fn exclude(self, field: impl AsRef<str>) -> QueryBuilder<F, WC, L, O, Se, So, Exclude> {
+        self.exclude_multiple(&[field.as_ref().to_owned()])
+    }
+
+    pub fn exclude_multiple(self, fields: &[String]) -> QueryBuilder<F, WC, L, O, Se, So, Exclude> {
+        QueryBuilder {
+            fields: self.fields,
+            where_clause: self.where_clause,
+            limit: self.limit,
+            offset: self.offset,
+            search: self.search,
+            sort: self.sort,
+            exclude: Exclude(fields.to_vec()),
+        }
+    }
+}
+
+impl<F, WC, L, O, Se, So> QueryBuilder<F, WC, L, O, Se, So, Exclude> {
+    pub fn exclude(mut self, field: impl AsRef<str>) -> Self {
+        self.exclude.0.push(field.as_ref().to_owned());
+        self
+    }
+
+    pub fn exclude_multiple(mut self, fields: &[String]) -> Self {
+        self.exclude.0.extend(fields.to_vec());
+        self
+    }
+}
+
+impl<F, WC, L, O, Se, So, Ex> QueryBuilder<F, WC, L, O, Se, So, Ex>
 where
     F: Display,
     WC: Display,
@@ -321,11 +420,22 @@ where
     O: Display,
     Se: Display,
     So: Display,
+    Ex: Display,
 {
+    pub(crate) fn has_sort(&self) -> bool {
+        !self.sort.to_string().is_empty()
+    }
+
     pub fn build(self) -> String {
         format!(
-            "{}{}{}{}{}{}",
-            self.fields, self.where_clause, self.search, self.offset, self.limit, self.sort
+            "{}{}{}{}{}{}{}",
+            self.fields,
+            self.exclude,
+            self.where_clause,
+            self.search,
+            self.offset,
+            self.limit,
+            self.sort
         )
         .trim()
         .to_owned()
@@ -334,6 +444,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::fields;
+    use crate::predicate::field;
     use crate::query::SortDirection;
 
     use super::Query;
@@ -405,6 +517,83 @@ mod tests {
         assert_eq!("fields *; search \"Halo\";", query)
     }
 
+    #[test]
+    fn search_is_escaped() {
+        let query = Query::search(r#"Halo"; fields *; where id = 1; search "x"#).build();
+        assert_eq!(
+            r#"fields *; search "Halo\"; fields *; where id = 1; search \"x";"#,
+            query
+        )
+    }
+
+    #[test]
+    fn typed_where_clause() {
+        let query = Query::where_(field("name").eq("Mario"))
+            .and(field("platforms").in_(&[18, 19]))
+            .build();
+        assert_eq!(
+            r#"fields *; where name = "Mario" & platforms = (18,19);"#,
+            query
+        )
+    }
+
+    #[test]
+    fn mixed_and_or_is_grouped() {
+        let query = Query::where_(field("id").eq(5))
+            .and(field("value").eq(42))
+            .or(field("name").ignore_case().starts_with("mario"))
+            .build();
+        assert_eq!(
+            r#"fields *; where (id = 5 & value = 42) | name ~ "mario"*;"#,
+            query
+        )
+    }
+
+    #[test]
+    fn only_exclude() {
+        let query = Query::exclude("summary").exclude("storyline").build();
+        assert_eq!("fields *; exclude summary, storyline;", query)
+    }
+
+    #[test]
+    fn exclude_with_where_clause() {
+        let query = Query::where_("id = 427")
+            .exclude_multiple(&["summary", "storyline"].map(String::from))
+            .build();
+        assert_eq!(
+            "fields *; exclude summary, storyline; where id = 427;",
+            query
+        )
+    }
+
+    #[test]
+    fn nested_field_paths() {
+        let game = fields::Game::root();
+        let query = Query::select(game.name())
+            .select(game.cover().image_id())
+            .select(game.platforms().name())
+            .select(game.release_dates().all_fields())
+            .build();
+        assert_eq!(
+            "fields name, cover.image_id, platforms.name, release_dates.*;",
+            query
+        )
+    }
+
+    #[test]
+    fn nested_object_path_selects_the_object() {
+        let game = fields::Game::root();
+        assert_eq!("cover", game.cover().path().as_ref());
+        assert_eq!(
+            "involved_companies.company.logo.image_id",
+            game.involved_companies()
+                .company()
+                .logo()
+                .image_id()
+                .as_ref()
+        );
+    }
+
     #[test]
     fn only_limit() {
         let query = Query::limit(10).build();
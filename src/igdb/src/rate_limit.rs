@@ -0,0 +1,233 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::{sleep, Instant};
+
+/// Limits applied to requests sent to IGDB. The defaults match the limits IGDB documents for its API.
+///
+/// Timing goes through `tokio::time`, so tests can drive the limiter with a paused clock.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// Sustained request rate, which is also the size of the burst allowed after a quiet period
+    pub requests_per_second: u32,
+
+    /// Maximum number of requests that can be waiting on a response at once
+    pub max_concurrent_requests: usize,
+
+    /// How many times a request rejected with `429 Too Many Requests` is retried before giving up
+    pub max_retries: u32,
+
+    /// Delay before the first retry of a rate limited request, doubled for every retry after that. A `Retry-After`
+    /// from IGDB takes precedence.
+    pub initial_backoff: Duration,
+
+    /// Longest delay before a retry, whether it comes from the backoff or from `Retry-After`
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 4,
+            max_concurrent_requests: 8,
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Delays between the retries of one rate limited request
+pub(crate) struct Backoff {
+    next: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    pub fn new(limits: &RateLimits) -> Self {
+        Self {
+            next: limits.initial_backoff.min(limits.max_backoff),
+            max: limits.max_backoff,
+        }
+    }
+
+    /// Sleeps for `retry_after` if IGDB sent one and for the next backoff otherwise, never longer than `max_backoff`
+    pub async fn wait(&mut self, retry_after: Option<Duration>) {
+        let delay = retry_after.unwrap_or(self.next).min(self.max);
+        self.next = self.next.saturating_mul(2).min(self.max);
+        sleep(delay).await;
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket for the request rate combined with a semaphore for open requests
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    bucket: Mutex<Bucket>,
+    open_requests: Semaphore,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let capacity = limits.requests_per_second.max(1) as f64;
+        Self {
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+            open_requests: Semaphore::new(limits.max_concurrent_requests.max(1)),
+            limits,
+        }
+    }
+
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
+    /// Waits until another request can be sent. The request counts as open until the returned permit is dropped.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .open_requests
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+
+        while let Some(wait) = self.try_take_token() {
+            sleep(wait).await;
+        }
+
+        permit
+    }
+
+    /// Takes a token if one is available, otherwise returns how long until the next one is
+    fn try_take_token(&self) -> Option<Duration> {
+        let rate = self.limits.requests_per_second.max(1) as f64;
+        let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::{timeout, Instant};
+
+    use super::{Backoff, RateLimiter, RateLimits};
+
+    fn limiter(requests_per_second: u32, max_concurrent_requests: usize) -> RateLimiter {
+        RateLimiter::new(RateLimits {
+            requests_per_second,
+            max_concurrent_requests,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn allows_a_burst_up_to_the_rate() {
+        let limiter = limiter(4, 8);
+        let start = Instant::now();
+
+        for _ in 0..4 {
+            drop(limiter.acquire().await);
+        }
+
+        assert_eq!(Duration::ZERO, start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spaces_requests_after_the_burst() {
+        let limiter = limiter(4, 8);
+        let start = Instant::now();
+
+        for _ in 0..6 {
+            drop(limiter.acquire().await);
+        }
+
+        assert_eq!(Duration::from_millis(500), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refills_after_a_quiet_period() {
+        let limiter = limiter(4, 8);
+        for _ in 0..4 {
+            drop(limiter.acquire().await);
+        }
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let start = Instant::now();
+        for _ in 0..4 {
+            drop(limiter.acquire().await);
+        }
+
+        assert_eq!(Duration::ZERO, start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn limits_open_requests() {
+        let limiter = limiter(100, 2);
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+
+        let blocked = timeout(Duration::from_secs(5), limiter.acquire()).await;
+        assert!(blocked.is_err());
+
+        drop(first);
+        let unblocked = timeout(Duration::from_secs(5), limiter.acquire()).await;
+        assert!(unblocked.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_doubles_up_to_the_max() {
+        let mut backoff = Backoff::new(&RateLimits {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..Default::default()
+        });
+
+        let mut delays = vec![];
+        for _ in 0..5 {
+            let start = Instant::now();
+            backoff.wait(None).await;
+            delays.push(start.elapsed().as_secs());
+        }
+        assert_eq!(vec![1, 2, 4, 5, 5], delays);
+
+        // Enough doublings to overflow `Duration` if they weren't capped
+        for _ in 0..100 {
+            backoff.wait(None).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_after_is_capped_at_the_max_backoff() {
+        let mut backoff = Backoff::new(&RateLimits {
+            max_backoff: Duration::from_secs(5),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        backoff.wait(Some(Duration::from_secs(2))).await;
+        assert_eq!(Duration::from_secs(2), start.elapsed());
+
+        let start = Instant::now();
+        backoff.wait(Some(Duration::from_secs(3600))).await;
+        assert_eq!(Duration::from_secs(5), start.elapsed());
+    }
+}
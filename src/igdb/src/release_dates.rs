@@ -0,0 +1,388 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+use crate::api::{DateFormatChangeDateCategoryEnum, Game, RegionRegionEnum, ReleaseDate};
+
+/// How precisely IGDB knows when something releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatePrecision {
+    Day,
+    Month,
+    Quarter(u32),
+    Year,
+    Tbd,
+}
+
+impl DatePrecision {
+    /// Lower is more precise
+    fn rank(&self) -> u8 {
+        match self {
+            DatePrecision::Day => 0,
+            DatePrecision::Month => 1,
+            DatePrecision::Quarter(_) => 2,
+            DatePrecision::Year => 3,
+            DatePrecision::Tbd => 4,
+        }
+    }
+}
+
+impl From<DateFormatChangeDateCategoryEnum> for DatePrecision {
+    fn from(category: DateFormatChangeDateCategoryEnum) -> Self {
+        match category {
+            DateFormatChangeDateCategoryEnum::Yyyymmmmdd => DatePrecision::Day,
+            DateFormatChangeDateCategoryEnum::Yyyymmmm => DatePrecision::Month,
+            DateFormatChangeDateCategoryEnum::Yyyy => DatePrecision::Year,
+            DateFormatChangeDateCategoryEnum::Yyyyq1 => DatePrecision::Quarter(1),
+            DateFormatChangeDateCategoryEnum::Yyyyq2 => DatePrecision::Quarter(2),
+            DateFormatChangeDateCategoryEnum::Yyyyq3 => DatePrecision::Quarter(3),
+            DateFormatChangeDateCategoryEnum::Yyyyq4 => DatePrecision::Quarter(4),
+            DateFormatChangeDateCategoryEnum::Tbd => DatePrecision::Tbd,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseStatus {
+    Released,
+    Upcoming,
+}
+
+/// A single release of a game in one region on one platform, with its date only as precise as IGDB knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedRelease {
+    pub region: RegionRegionEnum,
+    pub platform: Option<u64>,
+    /// Only set if `release_dates.platform` was expanded in the query
+    pub platform_name: Option<String>,
+    /// First day the release could fall on, `None` if it is TBD
+    pub date: Option<NaiveDate>,
+    pub precision: DatePrecision,
+}
+
+impl ResolvedRelease {
+    pub fn from_release_date(release_date: &ReleaseDate) -> Self {
+        // IGDB occasionally sends a fuzzy category without the year to go with it
+        let year = Some(release_date.y).filter(|&y| y > 0);
+        let (date, precision) = match DatePrecision::from(release_date.category()) {
+            DatePrecision::Day => {
+                let day = release_date
+                    .date
+                    .as_ref()
+                    .and_then(|date| DateTime::<Utc>::from_timestamp(date.seconds, 0))
+                    .map(|date| date.date_naive());
+                (day, DatePrecision::Day)
+            }
+            DatePrecision::Month => (
+                year.and_then(|y| NaiveDate::from_ymd_opt(y, release_date.m as u32, 1)),
+                DatePrecision::Month,
+            ),
+            DatePrecision::Quarter(quarter) => (
+                year.and_then(|y| NaiveDate::from_ymd_opt(y, quarter * 3 - 2, 1)),
+                DatePrecision::Quarter(quarter),
+            ),
+            DatePrecision::Year => (
+                year.and_then(|y| NaiveDate::from_ymd_opt(y, 1, 1)),
+                DatePrecision::Year,
+            ),
+            DatePrecision::Tbd => (None, DatePrecision::Tbd),
+        };
+
+        let platform = release_date.platform.as_ref();
+        ResolvedRelease {
+            region: release_date.region(),
+            platform: platform.map(|platform| platform.id).filter(|&id| id != 0),
+            platform_name: platform
+                .map(|platform| platform.name.clone())
+                .filter(|name| !name.is_empty()),
+            precision: if date.is_some() {
+                precision
+            } else {
+                DatePrecision::Tbd
+            },
+            date,
+        }
+    }
+
+    /// Last day the release could fall on, `None` if it is TBD
+    pub fn latest_date(&self) -> Option<NaiveDate> {
+        let date = self.date?;
+        match self.precision {
+            DatePrecision::Day => Some(date),
+            DatePrecision::Month => last_day_of_month(date.year(), date.month()),
+            DatePrecision::Quarter(quarter) => last_day_of_month(date.year(), quarter * 3),
+            DatePrecision::Year => NaiveDate::from_ymd_opt(date.year(), 12, 31),
+            DatePrecision::Tbd => None,
+        }
+    }
+
+    /// A fuzzy release only counts as released once its whole month, quarter or year has been reached
+    pub fn status_at(&self, today: NaiveDate) -> ReleaseStatus {
+        match self.latest_date() {
+            Some(latest) if latest <= today => ReleaseStatus::Released,
+            _ => ReleaseStatus::Upcoming,
+        }
+    }
+
+    pub fn status(&self) -> ReleaseStatus {
+        self.status_at(Utc::now().date_naive())
+    }
+
+    fn is_better_than(&self, other: &ResolvedRelease) -> bool {
+        (self.precision.rank(), self.date) < (other.precision.rank(), other.date)
+    }
+}
+
+/// Renders the date the way a person would say it, e.g. "Jul 14, 2025", "July 2025", "Q3 2025", "2025" or "TBD"
+impl Display for ResolvedRelease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.date, self.precision) {
+            (Some(date), DatePrecision::Day) => write!(f, "{}", date.format("%b %-d, %Y")),
+            (Some(date), DatePrecision::Month) => write!(f, "{}", date.format("%B %Y")),
+            (Some(date), DatePrecision::Quarter(quarter)) => {
+                write!(f, "Q{} {}", quarter, date.year())
+            }
+            (Some(date), DatePrecision::Year) => write!(f, "{}", date.year()),
+            _ => write!(f, "TBD"),
+        }
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+/// The best known release of a game for every region and platform it comes out on.
+///
+/// Needs a [`Game`] queried with `release_dates.*` (and `release_dates.platform.name` for platform names), since
+/// unexpanded release dates only carry their id.
+#[derive(Debug, Clone, Default)]
+pub struct GameReleases {
+    releases: Vec<ResolvedRelease>,
+}
+
+impl GameReleases {
+    /// When IGDB has several dates for the same region and platform, the most precise one wins, then the earliest
+    pub fn from_game(game: &Game) -> Self {
+        let mut releases: Vec<ResolvedRelease> = Vec::new();
+
+        let resolved = game
+            .release_dates
+            .iter()
+            .filter(|release_date| release_date.region() != RegionRegionEnum::RegionRegionNull)
+            .map(ResolvedRelease::from_release_date);
+
+        for release in resolved {
+            let existing = releases.iter_mut().find(|existing| {
+                existing.region == release.region && existing.platform == release.platform
+            });
+
+            match existing {
+                Some(existing) if release.is_better_than(existing) => *existing = release,
+                Some(_) => {}
+                None => releases.push(release),
+            }
+        }
+
+        releases.sort_by_key(|release| (release.date.is_none(), release.date));
+        GameReleases { releases }
+    }
+
+    /// Release in `region` on `platform`, falling back to a worldwide release on that platform
+    pub fn find(&self, region: RegionRegionEnum, platform: u64) -> Option<&ResolvedRelease> {
+        let on_platform = |region: RegionRegionEnum| {
+            self.releases
+                .iter()
+                .find(|release| release.region == region && release.platform == Some(platform))
+        };
+
+        on_platform(region).or_else(|| on_platform(RegionRegionEnum::Worldwide))
+    }
+
+    /// Earliest release with a known date, anywhere on anything
+    pub fn earliest(&self) -> Option<&ResolvedRelease> {
+        self.releases.iter().find(|release| release.date.is_some())
+    }
+
+    pub fn upcoming_at(&self, today: NaiveDate) -> impl Iterator<Item = &ResolvedRelease> {
+        self.releases
+            .iter()
+            .filter(move |release| release.status_at(today) == ReleaseStatus::Upcoming)
+    }
+
+    pub fn released_at(&self, today: NaiveDate) -> impl Iterator<Item = &ResolvedRelease> {
+        self.releases
+            .iter()
+            .filter(move |release| release.status_at(today) == ReleaseStatus::Released)
+    }
+
+    /// Releases ordered by date, with TBD releases last
+    pub fn iter(&self) -> impl Iterator<Item = &ResolvedRelease> {
+        self.releases.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::api::{
+        DateFormatChangeDateCategoryEnum, Game, Platform, RegionRegionEnum, ReleaseDate,
+    };
+
+    use super::{DatePrecision, GameReleases, ReleaseStatus, ResolvedRelease};
+
+    const PS5: u64 = 167;
+    const SWITCH: u64 = 130;
+
+    fn release_date(
+        region: RegionRegionEnum,
+        platform: u64,
+        category: DateFormatChangeDateCategoryEnum,
+        y: i32,
+        m: i32,
+        timestamp: i64,
+    ) -> ReleaseDate {
+        ReleaseDate {
+            region: region as i32,
+            platform: Some(Platform {
+                id: platform,
+                ..Default::default()
+            }),
+            category: category as i32,
+            y,
+            m,
+            date: Some(prost_types::Timestamp {
+                seconds: timestamp,
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn renders_fuzzy_dates() {
+        let render = |category, y, m, timestamp| {
+            ResolvedRelease::from_release_date(&release_date(
+                RegionRegionEnum::Europe,
+                PS5,
+                category,
+                y,
+                m,
+                timestamp,
+            ))
+            .to_string()
+        };
+
+        // 2025-07-14T00:00:00Z
+        assert_eq!(
+            "Jul 14, 2025",
+            render(
+                DateFormatChangeDateCategoryEnum::Yyyymmmmdd,
+                2025,
+                7,
+                1752451200
+            )
+        );
+        assert_eq!(
+            "July 2025",
+            render(DateFormatChangeDateCategoryEnum::Yyyymmmm, 2025, 7, 0)
+        );
+        assert_eq!(
+            "Q3 2025",
+            render(DateFormatChangeDateCategoryEnum::Yyyyq3, 2025, 9, 0)
+        );
+        assert_eq!(
+            "2025",
+            render(DateFormatChangeDateCategoryEnum::Yyyy, 2025, 12, 0)
+        );
+        assert_eq!(
+            "TBD",
+            render(DateFormatChangeDateCategoryEnum::Tbd, 2025, 0, 0)
+        );
+        assert_eq!(
+            "TBD",
+            render(DateFormatChangeDateCategoryEnum::Yyyyq3, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn fuzzy_releases_are_upcoming_until_their_period_ends() {
+        let release = ResolvedRelease::from_release_date(&release_date(
+            RegionRegionEnum::NorthAmerica,
+            PS5,
+            DateFormatChangeDateCategoryEnum::Yyyyq3,
+            2025,
+            9,
+            0,
+        ));
+
+        assert_eq!(DatePrecision::Quarter(3), release.precision);
+        assert_eq!(Some(day(2025, 7, 1)), release.date);
+        assert_eq!(Some(day(2025, 9, 30)), release.latest_date());
+        assert_eq!(ReleaseStatus::Upcoming, release.status_at(day(2025, 8, 15)));
+        assert_eq!(ReleaseStatus::Released, release.status_at(day(2025, 9, 30)));
+    }
+
+    #[test]
+    fn picks_the_most_precise_release_per_region_and_platform() {
+        let game = Game {
+            release_dates: vec![
+                release_date(
+                    RegionRegionEnum::NorthAmerica,
+                    PS5,
+                    DateFormatChangeDateCategoryEnum::Yyyy,
+                    2025,
+                    12,
+                    0,
+                ),
+                release_date(
+                    RegionRegionEnum::NorthAmerica,
+                    PS5,
+                    DateFormatChangeDateCategoryEnum::Yyyymmmm,
+                    2025,
+                    10,
+                    0,
+                ),
+                release_date(
+                    RegionRegionEnum::Worldwide,
+                    SWITCH,
+                    DateFormatChangeDateCategoryEnum::Tbd,
+                    0,
+                    0,
+                    0,
+                ),
+                // unexpanded release dates only have an id
+                ReleaseDate {
+                    id: 42,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let releases = GameReleases::from_game(&game);
+        assert_eq!(2, releases.iter().count());
+
+        let na_ps5 = releases.find(RegionRegionEnum::NorthAmerica, PS5).unwrap();
+        assert_eq!("October 2025", na_ps5.to_string());
+        assert_eq!(na_ps5, releases.earliest().unwrap());
+
+        let eu_switch = releases.find(RegionRegionEnum::Europe, SWITCH).unwrap();
+        assert_eq!(RegionRegionEnum::Worldwide, eu_switch.region);
+        assert_eq!("TBD", eu_switch.to_string());
+
+        assert!(releases.find(RegionRegionEnum::Europe, PS5).is_none());
+        assert_eq!(2, releases.upcoming_at(day(2025, 10, 30)).count());
+        assert_eq!(1, releases.released_at(day(2025, 10, 31)).count());
+    }
+}
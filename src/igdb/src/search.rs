@@ -0,0 +1,315 @@
+use chrono::{DateTime, Utc};
+
+use crate::api::{Game, GameCategoryEnum};
+use crate::client::IGDBClient;
+use crate::errors::IGDBClientError;
+use crate::game_client::SEARCH_LIMIT;
+use crate::pagination::MAX_PAGE_SIZE;
+use crate::predicate::field;
+use crate::query::Query;
+
+const SIMILARITY_WEIGHT: f64 = 0.55;
+const CATEGORY_WEIGHT: f64 = 0.2;
+const POPULARITY_WEIGHT: f64 = 0.15;
+const RECENCY_WEIGHT: f64 = 0.1;
+
+/// Rating count past which popularity stops adding to the score, roughly where IGDB's best known games sit
+const POPULAR_RATING_COUNT: f64 = 1000.0;
+
+/// How [`IGDBClient::search_games_ranked`] fetches and filters candidates
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// Games fetched from IGDB's own search to re-rank, so good matches it ranked low still get a chance
+    pub candidates: u32,
+    /// Most games returned after ranking
+    pub limit: u32,
+    /// Categories dropped entirely, both from the IGDB query and from the candidates
+    pub excluded_categories: Vec<GameCategoryEnum>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            candidates: 50,
+            limit: SEARCH_LIMIT,
+            excluded_categories: vec![
+                GameCategoryEnum::Bundle,
+                GameCategoryEnum::Mod,
+                GameCategoryEnum::Port,
+                GameCategoryEnum::Pack,
+                GameCategoryEnum::Update,
+            ],
+        }
+    }
+}
+
+impl SearchOptions {
+    fn is_excluded(&self, game: &Game) -> bool {
+        self.excluded_categories
+            .iter()
+            .any(|category| *category as i32 == game.category)
+    }
+}
+
+impl IGDBClient {
+    /// Searches IGDB for `query`, then re-ranks the candidates so the main game comes before its DLCs, bundles and
+    /// ports, see [`rank_games`]
+    pub async fn search_games_ranked(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<Game>, IGDBClientError> {
+        let search = Query::search(query).limit(options.candidates.clamp(1, MAX_PAGE_SIZE));
+        let search = if options.excluded_categories.is_empty() {
+            search.build()
+        } else {
+            let excluded: Vec<i32> = options
+                .excluded_categories
+                .iter()
+                .map(|category| *category as i32)
+                .collect();
+            search.where_(field("category").not_in(&excluded)).build()
+        };
+
+        let candidates = self.games(search).await?;
+        Ok(rank_games(query, candidates, options, Utc::now()))
+    }
+}
+
+/// Drops excluded categories and orders the rest by how well they match `query`, weighing title similarity the most,
+/// then preferring main games over editions and add-ons, then popularity and recency
+pub fn rank_games(
+    query: &str,
+    games: Vec<Game>,
+    options: &SearchOptions,
+    now: DateTime<Utc>,
+) -> Vec<Game> {
+    let mut scored: Vec<(f64, Game)> = games
+        .into_iter()
+        .filter(|game| !options.is_excluded(game))
+        .map(|game| (score(query, &game, now), game))
+        .collect();
+
+    // Stable, so games that score the same keep IGDB's order
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    scored
+        .into_iter()
+        .take(options.limit as usize)
+        .map(|(_, game)| game)
+        .collect()
+}
+
+fn score(query: &str, game: &Game, now: DateTime<Utc>) -> f64 {
+    SIMILARITY_WEIGHT * title_similarity(query, &game.name)
+        + CATEGORY_WEIGHT * category_preference(game)
+        + POPULARITY_WEIGHT * popularity(game)
+        + RECENCY_WEIGHT * recency(game, now)
+}
+
+/// How much a game looks like the thing people mean when they search its title, from 0 to 1
+fn category_preference(game: &Game) -> f64 {
+    // Editions like a "Game of the Year Edition" are main games with a version parent
+    if game.version_parent.is_some() {
+        return 0.3;
+    }
+
+    match GameCategoryEnum::try_from(game.category) {
+        Ok(GameCategoryEnum::MainGame) => 1.0,
+        Ok(
+            GameCategoryEnum::Remake
+            | GameCategoryEnum::Remaster
+            | GameCategoryEnum::ExpandedGame
+            | GameCategoryEnum::StandaloneExpansion,
+        ) => 0.6,
+        Ok(GameCategoryEnum::Expansion | GameCategoryEnum::Episode | GameCategoryEnum::Season) => {
+            0.3
+        }
+        _ => 0.0,
+    }
+}
+
+/// Rating count on a log scale, from 0 to 1
+fn popularity(game: &Game) -> f64 {
+    let count = f64::from(game.total_rating_count.max(0));
+    ((1.0 + count).ln() / (1.0 + POPULAR_RATING_COUNT).ln()).min(1.0)
+}
+
+/// 1 for unreleased games, halving over the first decade after release, and 0 without a date
+fn recency(game: &Game, now: DateTime<Utc>) -> f64 {
+    let Some(released) = &game.first_release_date else {
+        return 0.0;
+    };
+
+    let years = (now.timestamp() - released.seconds) as f64 / (365.25 * 24.0 * 60.0 * 60.0);
+    1.0 / (1.0 + years.max(0.0) / 10.0)
+}
+
+/// Lowercases and turns punctuation into spaces, so "Halo: Reach" and "halo reach" compare equal
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Sørensen–Dice coefficient over character bigrams, which tolerates typos and reordered words
+fn dice_coefficient(a: &str, b: &str) -> f64 {
+    let a = bigrams(a);
+    let mut b = bigrams(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let total = (a.len() + b.len()) as f64;
+    let mut matches = 0;
+    for bigram in a {
+        if let Some(i) = b.iter().position(|other| *other == bigram) {
+            b.swap_remove(i);
+            matches += 1;
+        }
+    }
+
+    2.0 * matches as f64 / total
+}
+
+/// How close `title` is to `query` from 0 to 1, ignoring case and punctuation. Titles that start with the whole query,
+/// like "Halo: Combat Evolved" for "halo", score at least 0.8.
+pub fn title_similarity(query: &str, title: &str) -> f64 {
+    let query = normalize(query);
+    let title = normalize(title);
+    if query.is_empty() || title.is_empty() {
+        return 0.0;
+    }
+    if query == title {
+        return 1.0;
+    }
+
+    let similarity = dice_coefficient(&query, &title);
+    let is_prefix = title
+        .strip_prefix(&query)
+        .is_some_and(|rest| rest.starts_with(' '));
+    if is_prefix {
+        similarity.max(0.8)
+    } else {
+        similarity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use wiremock::matchers::{body_string, method, path};
+    use wiremock::{Mock, MockServer};
+
+    use crate::api::{Game, GameCategoryEnum, GameResult};
+    use crate::client::Transport;
+    use crate::mock_server::*;
+
+    use super::{rank_games, title_similarity, SearchOptions};
+
+    fn game(id: u64, name: &str, category: GameCategoryEnum, rating_count: i32, year: i32) -> Game {
+        Game {
+            id,
+            name: name.to_owned(),
+            category: category as i32,
+            total_rating_count: rating_count,
+            first_release_date: Some(prost_types::Timestamp {
+                seconds: Utc
+                    .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+                    .unwrap()
+                    .timestamp(),
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn ids(games: &[Game]) -> Vec<u64> {
+        games.iter().map(|game| game.id).collect()
+    }
+
+    #[test]
+    fn titles_match_fuzzily() {
+        assert_eq!(1.0, title_similarity("halo reach", "Halo: Reach"));
+        assert!(title_similarity("halo", "Halo: Combat Evolved") >= 0.8);
+        assert!(title_similarity("final fantsy vii", "Final Fantasy VII") > 0.8);
+        assert!(title_similarity("halo", "Shadow of the Colossus") < 0.2);
+        assert_eq!(0.0, title_similarity("", "Halo"));
+    }
+
+    #[test]
+    fn main_games_outrank_their_add_ons() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let games = vec![
+            game(
+                1,
+                "Halo: Spartan Assault Pack",
+                GameCategoryEnum::Pack,
+                5,
+                2014,
+            ),
+            game(
+                2,
+                "Halo 3: ODST Map Pack",
+                GameCategoryEnum::DlcAddon,
+                20,
+                2009,
+            ),
+            game(3, "Halo Wars", GameCategoryEnum::MainGame, 400, 2009),
+            game(
+                4,
+                "Halo: Combat Evolved",
+                GameCategoryEnum::MainGame,
+                1500,
+                2001,
+            ),
+            game(5, "Halo: Combat Evolved", GameCategoryEnum::Port, 30, 2003),
+            game(6, "Halo Infinite", GameCategoryEnum::MainGame, 700, 2021),
+        ];
+
+        let ranked = rank_games("Halo", games.clone(), &SearchOptions::default(), now);
+        assert_eq!(vec![6, 4, 3, 2], ids(&ranked));
+
+        let options = SearchOptions {
+            limit: 2,
+            excluded_categories: vec![],
+            ..Default::default()
+        };
+        let ranked = rank_games("halo combat evolved", games, &options, now);
+        assert_eq!(vec![4, 5], ids(&ranked));
+    }
+
+    #[tokio::test]
+    async fn excludes_categories_in_the_query() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        let fixture = GameResult {
+            games: vec![final_fantasy_vii()],
+        };
+        Mock::given(method("POST"))
+            .and(path("/v4/games.pb"))
+            .and(body_string(
+                r#"fields *; where category != (3,5,11,13,14); search "final fantasy"; limit 50;"#,
+            ))
+            .respond_with(fixture_response(&fixture, Transport::Protobuf))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        let games = client
+            .search_games_ranked("final fantasy", &SearchOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(vec![427], ids(&games));
+    }
+}
@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::api::{Company, Game, Platform, ReleaseDate};
+use crate::errors::IGDBClientError;
+use crate::game_client::GameClient;
+use crate::pagination::MAX_PAGE_SIZE;
+use crate::search::{rank_games, SearchOptions};
+
+type Result<T> = std::result::Result<T, IGDBClientError>;
+
+/// [`GameClient`] answering from whatever it was filled with, for testing code built on IGDB without the network
+#[derive(Debug, Clone, Default)]
+pub struct FakeGameClient {
+    pub games: Vec<Game>,
+    pub release_dates: Vec<ReleaseDate>,
+    pub platforms: Vec<Platform>,
+    pub companies: Vec<Company>,
+}
+
+#[async_trait]
+impl GameClient for FakeGameClient {
+    /// Case-insensitive substring match on the name in place of IGDB's fuzzy search, ranked the same way as
+    /// [`crate::client::IGDBClient`] ranks its results
+    async fn search_games(&self, query: &str) -> Result<Vec<Game>> {
+        let lowercase = query.to_lowercase();
+        let matches = self
+            .games
+            .iter()
+            .filter(|game| game.name.to_lowercase().contains(&lowercase))
+            .cloned()
+            .collect();
+        Ok(rank_games(
+            query,
+            matches,
+            &SearchOptions::default(),
+            Utc::now(),
+        ))
+    }
+
+    async fn get_game(&self, id: u64) -> Result<Option<Game>> {
+        Ok(self.games.iter().find(|game| game.id == id).cloned())
+    }
+
+    async fn get_game_by_slug(&self, slug: &str) -> Result<Option<Game>> {
+        Ok(self.games.iter().find(|game| game.slug == slug).cloned())
+    }
+
+    async fn upcoming_releases(&self, platform: u64, limit: u32) -> Result<Vec<ReleaseDate>> {
+        let now = Utc::now().timestamp();
+        let mut upcoming: Vec<ReleaseDate> = self
+            .release_dates
+            .iter()
+            .filter(|release_date| {
+                release_date.platform.as_ref().map(|platform| platform.id) == Some(platform)
+            })
+            .filter(|release_date| {
+                release_date
+                    .date
+                    .as_ref()
+                    .is_some_and(|date| date.seconds > now)
+            })
+            .cloned()
+            .collect();
+
+        upcoming.sort_by_key(|release_date| release_date.date.as_ref().map(|date| date.seconds));
+        // Clamped like the real client does, which always asks IGDB for at least one result
+        upcoming.truncate(limit.clamp(1, MAX_PAGE_SIZE) as usize);
+        Ok(upcoming)
+    }
+
+    async fn get_platforms(&self) -> Result<Vec<Platform>> {
+        let mut platforms = self.platforms.clone();
+        platforms.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(platforms)
+    }
+
+    async fn get_companies(&self, ids: &[u64]) -> Result<Vec<Company>> {
+        Ok(self
+            .companies
+            .iter()
+            .filter(|company| ids.contains(&company.id))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::api::{Game, GameCategoryEnum, Platform, ReleaseDate};
+    use crate::game_client::GameClient;
+
+    use super::FakeGameClient;
+
+    const PS5: u64 = 167;
+
+    fn release_on(id: u64, platform: u64, seconds_from_now: i64) -> ReleaseDate {
+        ReleaseDate {
+            id,
+            platform: Some(Platform {
+                id: platform,
+                ..Default::default()
+            }),
+            date: Some(prost_types::Timestamp {
+                seconds: Utc::now().timestamp() + seconds_from_now,
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn fake() -> FakeGameClient {
+        FakeGameClient {
+            games: vec![
+                Game {
+                    id: 1,
+                    name: "Final Fantasy VII".to_owned(),
+                    slug: "final-fantasy-vii".to_owned(),
+                    ..Default::default()
+                },
+                Game {
+                    id: 2,
+                    name: "Final Fantasy VII Rebirth".to_owned(),
+                    slug: "final-fantasy-vii-rebirth".to_owned(),
+                    ..Default::default()
+                },
+            ],
+            release_dates: vec![
+                release_on(10, PS5, 3600 * 24 * 30),
+                release_on(11, PS5, -3600),
+                release_on(12, PS5, 3600),
+                release_on(13, 6, 3600),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_games() {
+        let client = fake();
+
+        let games = client.search_games("fantasy vii").await.unwrap();
+        assert_eq!(2, games.len());
+
+        let game = client.get_game_by_slug("final-fantasy-vii-rebirth").await;
+        assert_eq!(Some(2), game.unwrap().map(|game| game.id));
+        assert_eq!(None, client.get_game(3).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn search_ranks_main_games_first() {
+        let client = FakeGameClient {
+            games: vec![
+                Game {
+                    id: 1,
+                    name: "Final Fantasy VII Remake Bundle".to_owned(),
+                    category: GameCategoryEnum::Bundle as i32,
+                    ..Default::default()
+                },
+                Game {
+                    id: 2,
+                    name: "Final Fantasy VII: Episode Intermission".to_owned(),
+                    category: GameCategoryEnum::DlcAddon as i32,
+                    ..Default::default()
+                },
+                Game {
+                    id: 3,
+                    name: "Final Fantasy VII".to_owned(),
+                    category: GameCategoryEnum::MainGame as i32,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let games = client.search_games("final fantasy vii").await.unwrap();
+        assert_eq!(
+            vec![3, 2],
+            games.iter().map(|game| game.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn upcoming_releases_are_future_dates_on_the_platform_soonest_first() {
+        let client = fake();
+
+        let upcoming = client.upcoming_releases(PS5, 10).await.unwrap();
+        let ids: Vec<u64> = upcoming
+            .iter()
+            .map(|release_date| release_date.id)
+            .collect();
+        assert_eq!(vec![12, 10], ids);
+
+        assert_eq!(1, client.upcoming_releases(PS5, 1).await.unwrap().len());
+        assert_eq!(1, client.upcoming_releases(PS5, 0).await.unwrap().len());
+    }
+}
@@ -0,0 +1,478 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::headers::{self, Header, HeaderName, HeaderValue};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Router, TypedHeader};
+use reqwest::Method;
+use serde::Deserialize;
+use tokio::sync::mpsc::Sender;
+use tracing::{info, warn};
+
+use crate::api::{Game, GameResult, ReleaseDate, ReleaseDateResult};
+use crate::client::IGDBClient;
+use crate::endpoint::Endpoint;
+use crate::errors::IGDBClientError;
+
+/// Which change to an endpoint's objects a webhook fires on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookMethod {
+    Create,
+    Update,
+    Delete,
+}
+
+impl WebhookMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookMethod::Create => "create",
+            WebhookMethod::Update => "update",
+            WebhookMethod::Delete => "delete",
+        }
+    }
+}
+
+/// A webhook registered with IGDB
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    /// IGDB's id for the endpoint the webhook is registered on
+    pub category: u64,
+    /// `0` for create, `1` for delete and `2` for update
+    pub sub_category: u64,
+    pub active: bool,
+}
+
+impl Webhook {
+    pub fn method(&self) -> Option<WebhookMethod> {
+        match self.sub_category {
+            0 => Some(WebhookMethod::Create),
+            1 => Some(WebhookMethod::Delete),
+            2 => Some(WebhookMethod::Update),
+            _ => None,
+        }
+    }
+}
+
+/// IGDB answers with a single webhook or an array of them depending on the call
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Webhooks {
+    Many(Vec<Webhook>),
+    One(Webhook),
+}
+
+impl Webhooks {
+    fn into_vec(self) -> Vec<Webhook> {
+        match self {
+            Webhooks::Many(webhooks) => webhooks,
+            Webhooks::One(webhook) => vec![webhook],
+        }
+    }
+
+    fn parse(body: &[u8]) -> Result<Vec<Webhook>, IGDBClientError> {
+        Ok(serde_json::from_slice::<Webhooks>(body)?.into_vec())
+    }
+
+    fn parse_one(endpoint: &str, body: &[u8]) -> Result<Webhook, IGDBClientError> {
+        Self::parse(body)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| IGDBClientError::EmptyResponseError {
+                endpoint: endpoint.to_owned(),
+            })
+    }
+}
+
+/// URL to register for webhooks handled by [`webhook_router`] nested at `base_url`
+pub fn receiver_url<E: Endpoint>(base_url: &str, method: WebhookMethod) -> String {
+    format!(
+        "{}/{}/{}",
+        base_url.trim_end_matches('/'),
+        E::PATH,
+        method.as_str()
+    )
+}
+
+impl IGDBClient {
+    /// Asks IGDB to call `url` whenever an object of `E` is changed by `method`, sending `secret` in the `X-Secret`
+    /// header. IGDB doesn't say which method fired in the payload, so each method needs its own URL (see
+    /// [`receiver_url`]).
+    pub async fn register_webhook<E: Endpoint>(
+        &self,
+        url: &str,
+        method: WebhookMethod,
+        secret: &str,
+    ) -> Result<Webhook, IGDBClientError> {
+        let resource = format!("{}/webhooks/", E::PATH);
+        let form = [
+            ("url", url),
+            ("method", method.as_str()),
+            ("secret", secret),
+        ];
+
+        let body = self
+            .send(Method::POST, &resource, &resource, "", |request| {
+                request.form(&form)
+            })
+            .await?;
+        Webhooks::parse_one(&resource, &body)
+    }
+
+    pub async fn list_webhooks(&self) -> Result<Vec<Webhook>, IGDBClientError> {
+        let body = self
+            .send(Method::GET, "webhooks/", "webhooks", "", |request| request)
+            .await?;
+        Webhooks::parse(&body)
+    }
+
+    /// Deletes the webhook with `id`, returning it as it was
+    pub async fn delete_webhook(&self, id: u64) -> Result<Webhook, IGDBClientError> {
+        let resource = format!("webhooks/{}", id);
+        let body = self
+            .send(Method::DELETE, &resource, &resource, "", |request| request)
+            .await?;
+        Webhooks::parse_one(&resource, &body)
+    }
+}
+
+/// Object IGDB pushed to a webhook. Deletes only carry the id of the deleted object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookPayload {
+    Game(Box<Game>),
+    ReleaseDate(Box<ReleaseDate>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookEvent {
+    pub method: WebhookMethod,
+    pub payload: WebhookPayload,
+}
+
+pub struct Secret(pub String);
+
+static X_SECRET_HEADER_NAME: HeaderName = HeaderName::from_static("x-secret");
+
+impl Header for Secret {
+    fn name() -> &'static HeaderName {
+        &X_SECRET_HEADER_NAME
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        match value.to_str() {
+            Ok(secret) => Ok(Secret(secret.to_owned())),
+            Err(_) => Err(headers::Error::invalid()),
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            values.extend(std::iter::once(value));
+        }
+    }
+}
+
+struct ReceiverState {
+    secret: String,
+    events: Sender<WebhookEvent>,
+}
+
+/// Routes for IGDB webhooks on `games` and `release_dates`, sending every verified push to `events`. Meant to be
+/// nested into the bot's router, with webhooks registered at the matching [`receiver_url`].
+pub fn webhook_router(secret: String, events: Sender<WebhookEvent>) -> Router {
+    Router::new()
+        .route("/:endpoint/:method", post(igdb_webhook))
+        .with_state(Arc::new(ReceiverState { secret, events }))
+}
+
+async fn igdb_webhook(
+    Path((endpoint, method)): Path<(String, WebhookMethod)>,
+    secret: Option<TypedHeader<Secret>>,
+    State(state): State<Arc<ReceiverState>>,
+    body: String,
+) -> StatusCode {
+    info!("Incoming IGDB {} webhook for {}", method.as_str(), endpoint);
+    match handle_webhook(&endpoint, method, secret, &state, &body).await {
+        Ok(()) => StatusCode::OK,
+        Err(status) => status,
+    }
+}
+
+async fn handle_webhook(
+    endpoint: &str,
+    method: WebhookMethod,
+    secret: Option<TypedHeader<Secret>>,
+    state: &ReceiverState,
+    body: &str,
+) -> Result<(), StatusCode> {
+    match secret {
+        Some(TypedHeader(Secret(secret))) if secrets_match(&state.secret, &secret) => (),
+        _ => {
+            warn!("IGDB webhook had a missing or bad secret");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    let payload = if endpoint == GameResult::PATH {
+        serde_json::from_str(body).map(WebhookPayload::Game)
+    } else if endpoint == ReleaseDateResult::PATH {
+        serde_json::from_str(body).map(WebhookPayload::ReleaseDate)
+    } else {
+        warn!("Got IGDB webhook for unhandled endpoint {}", endpoint);
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let payload = payload.map_err(|e| {
+        warn!("Unable to decode IGDB webhook payload: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    // IGDB retries failed deliveries, so a full or closed channel is reported rather than waiting for room, which
+    // would hold the delivery open until IGDB gives up on it
+    state
+        .events
+        .try_send(WebhookEvent { method, payload })
+        .map_err(|e| {
+            warn!("Unable to queue IGDB webhook event: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        })
+}
+
+/// Compares in constant time, so the secret can't be guessed byte by byte from response times
+fn secrets_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::api::GameResult;
+    use crate::errors::IGDBClientError;
+    use crate::mock_server::*;
+
+    use super::{receiver_url, webhook_router, WebhookEvent, WebhookMethod, WebhookPayload};
+
+    const SECRET: &str = "webhook-secret";
+
+    fn webhook_json(id: u64, sub_category: u64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "url": "https://bot.example/igdb/games/update",
+            "category": 1,
+            "sub_category": sub_category,
+            "active": true,
+            "number_of_retries": 0,
+            "api_key": "ignored",
+            "secret": SECRET,
+            "created_at": 1700000000,
+            "updated_at": 1700000000,
+        })
+    }
+
+    #[test]
+    fn builds_receiver_urls() {
+        assert_eq!(
+            "https://bot.example/igdb/games/update",
+            receiver_url::<GameResult>("https://bot.example/igdb/", WebhookMethod::Update)
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_lists_and_deletes_webhooks() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/v4/games/webhooks/"))
+            .and(body_string_contains("method=update"))
+            .and(body_string_contains(format!("secret={}", SECRET)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(webhook_json(7, 2)))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v4/webhooks/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([webhook_json(7, 2), webhook_json(8, 0)])),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v4/webhooks/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(webhook_json(7, 2)))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        let url = receiver_url::<GameResult>("https://bot.example/igdb", WebhookMethod::Update);
+
+        let webhook = client
+            .register_webhook::<GameResult>(&url, WebhookMethod::Update, SECRET)
+            .await
+            .unwrap();
+        assert_eq!(7, webhook.id);
+        assert_eq!(Some(WebhookMethod::Update), webhook.method());
+
+        let webhooks = client.list_webhooks().await.unwrap();
+        assert_eq!(
+            vec![7, 8],
+            webhooks.iter().map(|w| w.id).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(WebhookMethod::Create), webhooks[1].method());
+
+        assert_eq!(7, client.delete_webhook(7).await.unwrap().id);
+    }
+
+    #[tokio::test]
+    async fn empty_response_is_an_error() {
+        let server = MockServer::start().await;
+        mock_token_endpoint(&server).await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v4/webhooks/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let client = offline_client(&server);
+        match client.delete_webhook(7).await {
+            Err(IGDBClientError::EmptyResponseError { endpoint }) => {
+                assert_eq!("webhooks/7", endpoint)
+            }
+            other => panic!("expected an empty response error, got {:?}", other),
+        }
+    }
+
+    fn push(uri: &str, secret: Option<&str>, body: &str) -> Request<Body> {
+        let mut request = Request::post(uri).header("content-type", "application/json");
+        if let Some(secret) = secret {
+            request = request.header("X-Secret", secret);
+        }
+        request.body(Body::from(body.to_owned())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivers_verified_pushes() {
+        let (events_tx, mut events_rx) = mpsc::channel(8);
+        let router = webhook_router(SECRET.to_owned(), events_tx);
+
+        let game = r#"{"id": 427, "name": "Final Fantasy VII", "platforms": [7]}"#;
+        let response = router
+            .clone()
+            .oneshot(push("/games/update", Some(SECRET), game))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        let response = router
+            .oneshot(push("/release_dates/delete", Some(SECRET), r#"{"id": 9}"#))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        match events_rx.recv().await.unwrap() {
+            WebhookEvent {
+                method: WebhookMethod::Update,
+                payload: WebhookPayload::Game(game),
+            } => {
+                assert_eq!("Final Fantasy VII", game.name);
+                assert_eq!(7, game.platforms[0].id);
+            }
+            other => panic!("expected a game update, got {:?}", other),
+        }
+
+        match events_rx.recv().await.unwrap() {
+            WebhookEvent {
+                method: WebhookMethod::Delete,
+                payload: WebhookPayload::ReleaseDate(release_date),
+            } => assert_eq!(9, release_date.id),
+            other => panic!("expected a release date delete, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_pushes() {
+        let (events_tx, mut events_rx) = mpsc::channel(8);
+        let router = webhook_router(SECRET.to_owned(), events_tx);
+
+        let cases = [
+            (
+                "/games/update",
+                None,
+                r#"{"id": 1}"#,
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                "/games/update",
+                Some("wrong"),
+                r#"{"id": 1}"#,
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                "/covers/update",
+                Some(SECRET),
+                r#"{"id": 1}"#,
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                "/games/update",
+                Some(SECRET),
+                "not json",
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+
+        for (uri, secret, body, status) in cases {
+            let response = router
+                .clone()
+                .oneshot(push(uri, secret, body))
+                .await
+                .unwrap();
+            assert_eq!(status, response.status(), "{}", uri);
+        }
+
+        drop(router);
+        assert!(events_rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn full_channel_is_unavailable() {
+        let (events_tx, _events_rx) = mpsc::channel(1);
+        let router = webhook_router(SECRET.to_owned(), events_tx);
+
+        let game = r#"{"id": 427}"#;
+        let response = router
+            .clone()
+            .oneshot(push("/games/update", Some(SECRET), game))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        let response = router
+            .oneshot(push("/games/update", Some(SECRET), game))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+    }
+}
@@ -1,11 +1,16 @@
 #![allow(unused)]
 
+use std::time::Duration;
+
 use async_trait::async_trait;
-use reqwest::header;
+use reqwest::{header, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::time::sleep;
 
 use crate::errors::TMDBClientError;
 use crate::model::*;
+use crate::search::MovieSearch;
 
 const TMDB_BASE_URL: &str = "https://api.themoviedb.org/3";
 
@@ -13,16 +18,76 @@ type Result<T> = std::result::Result<T, TMDBClientError>;
 
 #[async_trait]
 pub trait MovieClient {
+    /// First page of results for `query`, see [`MovieClient::search_movies`] for the other pages and filters
     async fn search_movie(&self, query: &str) -> Result<Vec<MovieSearchResult>>;
+    async fn search_movies(&self, search: &MovieSearch) -> Result<MovieSearchResponse>;
     async fn get_movie(&self, id: TMDBId) -> Result<Movie>;
 }
 
+#[async_trait]
+pub trait TvClient {
+    async fn search_tv(&self, query: &str) -> Result<Vec<TvSearchResult>>;
+    async fn get_tv(&self, id: TMDBId) -> Result<TvSeries>;
+    async fn get_season(&self, id: TMDBId, season_number: u32) -> Result<Season>;
+}
+
+/// How requests that fail with `429 Too Many Requests` or a server error are retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt before the error is returned
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled for every retry after that. A `Retry-After` from TMDB takes precedence.
+    pub initial_backoff: Duration,
+
+    /// Longest delay before a retry. If TMDB asks to wait longer with `Retry-After`, the error is returned instead.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The body TMDB sends with errors, e.g. `{"status_code": 34, "status_message": "...", "success": false}`
+#[derive(Debug, Default, Deserialize)]
+struct ErrorPayload {
+    status_message: Option<String>,
+}
+
+impl ErrorPayload {
+    fn message(status: StatusCode, body: &str) -> String {
+        serde_json::from_str::<ErrorPayload>(body)
+            .ok()
+            .and_then(|payload| payload.status_message)
+            .unwrap_or_else(|| {
+                status
+                    .canonical_reason()
+                    .unwrap_or("unknown error")
+                    .to_owned()
+            })
+    }
+}
+
+#[derive(Clone)]
 pub struct TMDBClient {
     client: reqwest::Client,
+    base_url: String,
+    retry_policy: RetryPolicy,
 }
 
 impl TMDBClient {
     pub fn new(api_token: &str) -> Result<Self> {
+        Self::with_base_url(api_token, TMDB_BASE_URL)
+    }
+
+    /// Creates a client that sends requests to `base_url` instead of TMDB, e.g. a proxy or a local stand-in for tests
+    pub fn with_base_url(api_token: &str, base_url: &str) -> Result<Self> {
         let mut token = header::HeaderValue::from_str(&format!("Bearer {}", api_token))?;
         token.set_sensitive(true);
         let mut default_headers = header::HeaderMap::new();
@@ -32,37 +97,107 @@ impl TMDBClient {
             client: reqwest::Client::builder()
                 .default_headers(default_headers)
                 .build()?,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            retry_policy: RetryPolicy::default(),
         })
     }
 
-    async fn make_request<'a, T>(&self, endpoint: &str, params: &Vec<(&str, &str)>) -> Result<T>
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    async fn make_request<T>(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let url = format!("{}/{}", TMDB_BASE_URL, endpoint);
-        let response = self
-            .client
-            .get(url)
-            .query(params)
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        Ok(serde_json::from_str::<T>(&response)?)
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let mut backoff = self
+            .retry_policy
+            .initial_backoff
+            .min(self.retry_policy.max_backoff);
+        let mut retries = 0;
+        loop {
+            let response = self.client.get(&url).query(params).send().await?;
+            let status = response.status();
+            let retry_after = retry_after(response.headers());
+            let body = response.text().await?;
+
+            if status.is_success() {
+                return Ok(serde_json::from_str::<T>(&body)?);
+            }
+
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            let delay = retry_after.unwrap_or(backoff);
+            if !retryable
+                || retries >= self.retry_policy.max_retries
+                || delay > self.retry_policy.max_backoff
+            {
+                return Err(Self::status_error(status, retry_after, &body));
+            }
+
+            retries += 1;
+            sleep(delay).await;
+            backoff = backoff.saturating_mul(2).min(self.retry_policy.max_backoff);
+        }
     }
+
+    fn status_error(
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        body: &str,
+    ) -> TMDBClientError {
+        let message = ErrorPayload::message(status, body);
+        match status {
+            StatusCode::NOT_FOUND => TMDBClientError::NotFoundError { message },
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                TMDBClientError::UnauthorizedError {
+                    status: status.as_u16(),
+                    message,
+                }
+            }
+            StatusCode::TOO_MANY_REQUESTS => TMDBClientError::RateLimitedError { retry_after },
+            status if status.is_server_error() => TMDBClientError::UpstreamError {
+                status: status.as_u16(),
+                message,
+            },
+            status => TMDBClientError::BadRequestError {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+/// `Retry-After` in seconds, the only form TMDB sends it in
+fn retry_after(headers: &header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
 }
 
 #[async_trait]
 impl MovieClient for TMDBClient {
     async fn search_movie(&self, query: &str) -> Result<Vec<MovieSearchResult>> {
-        let params = vec![("include_adult", "false"), ("query", query)];
-        let result = self
-            .make_request::<MovieSearchResponse>("search/movie", &params)
-            .await;
+        let result = self.search_movies(&MovieSearch::new(query)).await;
         Ok(result?.results)
     }
 
+    async fn search_movies(&self, search: &MovieSearch) -> Result<MovieSearchResponse> {
+        let params = search.params();
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        self.make_request::<MovieSearchResponse>("search/movie", &params)
+            .await
+    }
+
     async fn get_movie(&self, id: TMDBId) -> Result<Movie> {
         let params = vec![("append_to_response", "release_dates")];
         let endpoint = format!("movie/{}", id);
@@ -71,25 +206,46 @@ impl MovieClient for TMDBClient {
     }
 }
 
+#[async_trait]
+impl TvClient for TMDBClient {
+    async fn search_tv(&self, query: &str) -> Result<Vec<TvSearchResult>> {
+        let params = vec![("include_adult", "false"), ("query", query)];
+        let result = self
+            .make_request::<TvSearchResponse>("search/tv", &params)
+            .await;
+        Ok(result?.results)
+    }
+
+    async fn get_tv(&self, id: TMDBId) -> Result<TvSeries> {
+        let endpoint = format!("tv/{}", id);
+        self.make_request::<TvSeries>(&endpoint, &[]).await
+    }
+
+    async fn get_season(&self, id: TMDBId, season_number: u32) -> Result<Season> {
+        let endpoint = format!("tv/{}/season/{}", id, season_number);
+        self.make_request::<Season>(&endpoint, &[]).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::env;
     use std::str::FromStr;
+    use std::time::Duration;
 
-    use chrono::DateTime;
-    use chrono::{NaiveDateTime, Utc};
-    use dotenvy::dotenv;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    use crate::client::{MovieClient, TMDBClient};
+    use crate::client::{MovieClient, RetryPolicy, TvClient};
     use crate::errors::TMDBClientError;
-    use crate::model::Movie;
+    use crate::mock_server::*;
+    use crate::model::TvStatus;
 
     #[tokio::test]
     async fn it_can_search_for_movies() {
-        dotenv().ok();
-
-        let api_token = env::var("TMDB_TOKEN").expect("couldn't find TMDB token");
-        let client = TMDBClient::new(&api_token).expect("couldn't create client");
+        let server = MockServer::start().await;
+        mock_search(&server, "avengers", AVENGERS_SEARCH).await;
+        let client = offline_client(&server);
 
         let movies = client
             .search_movie("avengers")
@@ -108,12 +264,10 @@ mod tests {
 
     #[tokio::test]
     async fn it_can_search_for_movies_without_release_date() {
-        dotenv().ok();
-
-        let api_token = env::var("TMDB_TOKEN").expect("couldn't find TMDB token");
-        let client = TMDBClient::new(&api_token).expect("couldn't create client");
+        let server = MockServer::start().await;
+        mock_search(&server, "Armor Wars", ARMOR_WARS_SEARCH).await;
+        let client = offline_client(&server);
 
-        // TODO: This test will break when Armor Wars gets a release date...
         let movies = client
             .search_movie("Armor Wars")
             .await
@@ -125,10 +279,9 @@ mod tests {
 
     #[tokio::test]
     async fn it_can_lookup_a_movie() {
-        dotenv().ok();
-
-        let api_token = env::var("TMDB_TOKEN").expect("couldn't find TMDB token");
-        let client = TMDBClient::new(&api_token).expect("couldn't create client");
+        let server = MockServer::start().await;
+        mock_movie(&server, 24428, AVENGERS_MOVIE).await;
+        let client = offline_client(&server);
 
         let movie = client
             .get_movie(24428.into())
@@ -148,7 +301,243 @@ mod tests {
 
         assert_eq!(
             british_release_date,
-            DateTime::<Utc>::from_str(&"2012-04-26T00:00:00.000Z").unwrap()
+            DateTime::<Utc>::from_str("2012-04-26T00:00:00.000Z").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_go_to_the_configured_base_url() {
+        let server = MockServer::start().await;
+        mock_movie(&server, 24428, AVENGERS_MOVIE).await;
+        let client = offline_client(&server);
+
+        client.get_movie(24428.into()).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("/3/movie/24428", requests[0].url.path());
+    }
+
+    #[tokio::test]
+    async fn it_can_search_for_tv() {
+        let server = MockServer::start().await;
+        mock_search_tv(&server, "severance", SEVERANCE_SEARCH).await;
+        let client = offline_client(&server);
+
+        let shows = client.search_tv("severance").await.unwrap();
+
+        assert_eq!(2, shows.len());
+        assert_eq!(shows[0].id, 95396.into());
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2022, 2, 17),
+            shows[0].first_air_date
         );
+        assert_eq!(None, shows[1].first_air_date);
+    }
+
+    #[tokio::test]
+    async fn it_can_lookup_a_tv_series() {
+        let server = MockServer::start().await;
+        mock_tv(&server, 95396, SEVERANCE_TV).await;
+        let client = offline_client(&server);
+
+        let series = client.get_tv(95396.into()).await.unwrap();
+
+        assert_eq!(TvStatus::ReturningSeries, series.status);
+        assert_eq!("Apple TV+", series.networks[0].name);
+        assert_eq!(3, series.seasons.len());
+        assert_eq!(None, series.seasons[2].air_date);
+
+        let last = series.last_episode_to_air.expect("no last episode");
+        assert_eq!((2, 10), (last.season_number, last.episode_number));
+
+        let next = series.next_episode_to_air.expect("no next episode");
+        assert!(next.is_season_premiere());
+        assert_eq!(NaiveDate::from_ymd_opt(2027, 1, 15), next.air_date);
+        assert_eq!(None, next.runtime);
+    }
+
+    #[tokio::test]
+    async fn it_can_lookup_a_season() {
+        let server = MockServer::start().await;
+        mock_season(&server, 95396, 2, SEVERANCE_SEASON_2).await;
+        let client = offline_client(&server);
+
+        let season = client.get_season(95396.into(), 2).await.unwrap();
+
+        assert_eq!(2, season.season_number);
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 1, 17), season.air_date);
+        let air_dates: Vec<_> = season.episodes.iter().map(|e| e.air_date).collect();
+        assert_eq!(
+            vec![
+                NaiveDate::from_ymd_opt(2025, 1, 17),
+                NaiveDate::from_ymd_opt(2025, 1, 24),
+                NaiveDate::from_ymd_opt(2025, 3, 21)
+            ],
+            air_dates
+        );
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(100),
+        }
+    }
+
+    fn error_response(status: u16, status_code: u32, message: &str) -> ResponseTemplate {
+        ResponseTemplate::new(status).set_body_json(serde_json::json!({
+            "status_code": status_code,
+            "status_message": message,
+            "success": false,
+        }))
+    }
+
+    async fn mount_error(server: &MockServer, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path("/3/movie/24428"))
+            .respond_with(response)
+            .with_priority(1)
+            .mount(server)
+            .await;
+    }
+
+    async fn requests(server: &MockServer) -> usize {
+        server.received_requests().await.unwrap().len()
+    }
+
+    #[tokio::test]
+    async fn error_payloads_become_errors() {
+        let server = MockServer::start().await;
+        let message = "The resource you requested could not be found.";
+        mount_error(&server, error_response(404, 34, message)).await;
+        let client = offline_client(&server);
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(
+            result,
+            Err(TMDBClientError::NotFoundError { message: m }) if m == message
+        ));
+        assert_eq!(1, requests(&server).await);
+    }
+
+    #[tokio::test]
+    async fn unauthorized_is_not_retried() {
+        let server = MockServer::start().await;
+        let message = "Invalid API key: You must be granted a valid key.";
+        mount_error(&server, error_response(401, 7, message)).await;
+        let client = offline_client(&server);
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(
+            result,
+            Err(TMDBClientError::UnauthorizedError { status: 401, message: m }) if m == message
+        ));
+        assert_eq!(1, requests(&server).await);
+    }
+
+    #[tokio::test]
+    async fn rate_limits_and_server_errors_are_retried() {
+        let server = MockServer::start().await;
+        mock_movie(&server, 24428, AVENGERS_MOVIE).await;
+        Mock::given(method("GET"))
+            .and(path("/3/movie/24428"))
+            .respond_with(error_response(
+                429,
+                25,
+                "Your request count is over the allowed limit.",
+            ))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/3/movie/24428"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(2)
+            .mount(&server)
+            .await;
+        let client = offline_client(&server).retry_policy(fast_retries());
+
+        let movie = client.get_movie(24428.into()).await.unwrap();
+
+        assert_eq!("The Avengers", movie.title);
+        assert_eq!(3, requests(&server).await);
+    }
+
+    #[tokio::test]
+    async fn retries_are_bounded() {
+        let server = MockServer::start().await;
+        mount_error(&server, ResponseTemplate::new(502)).await;
+        let client = offline_client(&server).retry_policy(fast_retries());
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(
+            result,
+            Err(TMDBClientError::UpstreamError { status: 502, message: m }) if m == "Bad Gateway"
+        ));
+        assert_eq!(3, requests(&server).await);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_errors_carry_retry_after() {
+        let server = MockServer::start().await;
+        mount_error(
+            &server,
+            ResponseTemplate::new(429).insert_header("Retry-After", "7"),
+        )
+        .await;
+        let client = offline_client(&server).retry_policy(RetryPolicy {
+            max_retries: 0,
+            ..fast_retries()
+        });
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(
+            result,
+            Err(TMDBClientError::RateLimitedError { retry_after: Some(d) }) if d == Duration::from_secs(7)
+        ));
+    }
+
+    #[tokio::test]
+    async fn retry_after_beyond_max_backoff_is_not_waited_for() {
+        let server = MockServer::start().await;
+        mount_error(
+            &server,
+            ResponseTemplate::new(429).insert_header("Retry-After", "3600"),
+        )
+        .await;
+        let client = offline_client(&server).retry_policy(fast_retries());
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(
+            result,
+            Err(TMDBClientError::RateLimitedError { retry_after: Some(d) }) if d == Duration::from_secs(3600)
+        ));
+        assert_eq!(1, requests(&server).await);
+    }
+
+    #[tokio::test]
+    async fn backoff_stops_doubling_at_max_backoff() {
+        let server = MockServer::start().await;
+        mount_error(&server, ResponseTemplate::new(503)).await;
+        // Enough doublings of the backoff to overflow `Duration` if it weren't capped
+        let client = offline_client(&server).retry_policy(RetryPolicy {
+            max_retries: 80,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        });
+
+        let result = client.get_movie(24428.into()).await;
+
+        assert!(matches!(result, Err(TMDBClientError::UpstreamError { .. })));
+        assert_eq!(81, requests(&server).await);
     }
 }
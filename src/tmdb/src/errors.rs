@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use reqwest::header::InvalidHeaderValue;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -15,4 +17,22 @@ pub enum TMDBClientError {
     #[error("json error")]
     #[serde(skip)]
     ClientDeserializationError(#[from] serde_json::Error),
+
+    #[error("not found on TMDB: {message}")]
+    NotFoundError { message: String },
+
+    #[error("not authorized to use the TMDB API ({status}): {message}")]
+    UnauthorizedError { status: u16, message: String },
+
+    #[error("rate limited by TMDB")]
+    RateLimitedError {
+        /// How long TMDB asked to wait before trying again, if it said
+        retry_after: Option<Duration>,
+    },
+
+    #[error("TMDB failed to handle the request ({status}): {message}")]
+    UpstreamError { status: u16, message: String },
+
+    #[error("bad request ({status}): {message}")]
+    BadRequestError { status: u16, message: String },
 }
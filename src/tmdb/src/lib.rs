@@ -1,3 +1,6 @@
 pub mod client;
 pub mod errors;
+#[cfg(test)]
+mod mock_server;
 pub mod model;
+pub mod search;
@@ -0,0 +1,92 @@
+//! Helpers for running [`TMDBClient`] against recorded TMDB responses instead of the live API
+
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::client::TMDBClient;
+
+pub const TEST_TOKEN: &str = "test-token";
+
+pub const AVENGERS_SEARCH: &str = include_str!("../fixtures/search_movie_avengers.json");
+pub const ARMOR_WARS_SEARCH: &str = include_str!("../fixtures/search_movie_armor_wars.json");
+/// `movie/24428?append_to_response=release_dates`
+pub const AVENGERS_MOVIE: &str = include_str!("../fixtures/movie_24428.json");
+pub const DUNE_SEARCH_PAGE_1: &str = include_str!("../fixtures/search_movie_dune_page_1.json");
+pub const DUNE_SEARCH_PAGE_2: &str = include_str!("../fixtures/search_movie_dune_page_2.json");
+pub const DUNE_1984_SEARCH: &str = include_str!("../fixtures/search_movie_dune_1984.json");
+pub const SEVERANCE_SEARCH: &str = include_str!("../fixtures/search_tv_severance.json");
+pub const SEVERANCE_TV: &str = include_str!("../fixtures/tv_95396.json");
+pub const SEVERANCE_SEASON_2: &str = include_str!("../fixtures/tv_95396_season_2.json");
+
+fn json_response(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+/// Matches requests the way TMDB would accept them, authenticated with [`TEST_TOKEN`]
+fn tmdb_get(endpoint: &str) -> wiremock::MockBuilder {
+    Mock::given(method("GET"))
+        .and(path(format!("/3/{}", endpoint)))
+        .and(header(
+            "authorization",
+            format!("Bearer {}", TEST_TOKEN).as_str(),
+        ))
+}
+
+async fn mock_search_endpoint(server: &MockServer, endpoint: &str, query: &str, fixture: &str) {
+    tmdb_get(endpoint)
+        .and(query_param("query", query))
+        .and(query_param("include_adult", "false"))
+        .respond_with(json_response(fixture))
+        .mount(server)
+        .await;
+}
+
+pub async fn mock_search(server: &MockServer, query: &str, fixture: &str) {
+    mock_search_endpoint(server, "search/movie", query, fixture).await;
+}
+
+/// Serves `fixture` for movie searches for `query` that also have every one of `params`
+pub async fn mock_search_with(
+    server: &MockServer,
+    query: &str,
+    params: &[(&str, &str)],
+    fixture: &str,
+) {
+    let mut mock = tmdb_get("search/movie").and(query_param("query", query));
+    for (name, value) in params {
+        mock = mock.and(query_param(*name, *value));
+    }
+    mock.respond_with(json_response(fixture))
+        .mount(server)
+        .await;
+}
+
+pub async fn mock_search_tv(server: &MockServer, query: &str, fixture: &str) {
+    mock_search_endpoint(server, "search/tv", query, fixture).await;
+}
+
+pub async fn mock_movie(server: &MockServer, id: u32, fixture: &str) {
+    tmdb_get(&format!("movie/{}", id))
+        .and(query_param("append_to_response", "release_dates"))
+        .respond_with(json_response(fixture))
+        .mount(server)
+        .await;
+}
+
+pub async fn mock_tv(server: &MockServer, id: u32, fixture: &str) {
+    tmdb_get(&format!("tv/{}", id))
+        .respond_with(json_response(fixture))
+        .mount(server)
+        .await;
+}
+
+pub async fn mock_season(server: &MockServer, id: u32, season_number: u32, fixture: &str) {
+    tmdb_get(&format!("tv/{}/season/{}", id, season_number))
+        .respond_with(json_response(fixture))
+        .mount(server)
+        .await;
+}
+
+pub fn offline_client(server: &MockServer) -> TMDBClient {
+    TMDBClient::with_base_url(TEST_TOKEN, &format!("{}/3", server.uri())).unwrap()
+}
@@ -2,13 +2,13 @@
 
 use std::fmt::{Display, Formatter};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_repr::Deserialize_repr;
 use serde_with::serde_as;
 use serde_with::NoneAsEmptyString;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize)]
 #[serde(transparent)]
 pub struct TMDBId(u32);
 
@@ -18,9 +18,9 @@ impl PartialEq for TMDBId {
     }
 }
 
-impl Into<TMDBId> for u32 {
-    fn into(self) -> TMDBId {
-        TMDBId(self)
+impl From<u32> for TMDBId {
+    fn from(value: u32) -> Self {
+        TMDBId(value)
     }
 }
 
@@ -48,7 +48,10 @@ pub struct MovieSearchResult {
 
 #[derive(Debug, Deserialize)]
 pub struct MovieSearchResponse {
+    pub page: u32,
     pub results: Vec<MovieSearchResult>,
+    pub total_pages: u32,
+    pub total_results: u32,
 }
 
 #[serde_as]
@@ -61,12 +64,75 @@ pub struct Movie {
 
     pub title: String,
     pub runtime: u32,
+
+    /// TMDB's primary release date, usually the earliest theatrical one
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub release_date: Option<chrono::NaiveDate>,
 }
 
 impl Movie {
     pub fn release_dates(&self) -> &Vec<MovieRegionReleaseDates> {
         &self.release_dates.results
     }
+
+    fn dated_releases(&self) -> impl Iterator<Item = (&MovieReleaseDate, DateTime<Utc>)> {
+        self.release_dates()
+            .iter()
+            .flat_map(|region| region.release_dates.iter())
+            .filter_map(|release| release.release_date.map(|date| (release, date)))
+    }
+
+    /// Earliest dated release of one of `release_types` in `region` (ISO 3166-1 code, e.g. "US")
+    pub fn release_in(
+        &self,
+        region: &str,
+        release_types: &[ReleaseType],
+    ) -> Option<&MovieReleaseDate> {
+        self.release_dates()
+            .iter()
+            .filter(|dates| dates.iso_3166_1 == region)
+            .flat_map(|dates| dates.release_dates.iter())
+            .filter(|release| release_types.contains(&release.release_type))
+            .filter(|release| release.release_date.is_some())
+            .min_by_key(|release| release.release_date)
+    }
+
+    /// When the movie reaches theaters in `region`, falling back to the primary release date and then to the earliest
+    /// theatrical release anywhere if TMDB has no theatrical date for the region
+    pub fn theatrical_release(&self, region: &str) -> Option<DateTime<Utc>> {
+        const THEATRICAL: [ReleaseType; 2] =
+            [ReleaseType::Theatrical, ReleaseType::TheatricalLimited];
+
+        self.release_in(region, &THEATRICAL)
+            .and_then(|release| release.release_date)
+            .or_else(|| {
+                self.release_date
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| date.and_utc())
+            })
+            .or_else(|| {
+                self.dated_releases()
+                    .filter(|(release, _)| THEATRICAL.contains(&release.release_type))
+                    .map(|(_, date)| date)
+                    .min()
+            })
+    }
+
+    /// When the movie can be bought or streamed in `region`, without any fallback since digital releases vary too
+    /// much between regions to guess
+    pub fn digital_release(&self, region: &str) -> Option<DateTime<Utc>> {
+        self.release_in(region, &[ReleaseType::Digital])
+            .and_then(|release| release.release_date)
+    }
+
+    /// Earliest premiere in any region, or the earliest release of any kind if TMDB lists no premiere
+    pub fn earliest_premiere(&self) -> Option<DateTime<Utc>> {
+        self.dated_releases()
+            .filter(|(release, _)| release.release_type == ReleaseType::Premiere)
+            .map(|(_, date)| date)
+            .min()
+            .or_else(|| self.dated_releases().map(|(_, date)| date).min())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -81,7 +147,7 @@ pub struct MovieRegionReleaseDates {
     pub release_dates: Vec<MovieReleaseDate>,
 }
 
-#[derive(Debug, Deserialize_repr, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize_repr, PartialEq)]
 #[repr(u8)]
 pub enum ReleaseType {
     Premiere = 1,
@@ -100,4 +166,207 @@ pub struct MovieReleaseDate {
 
     #[serde_as(as = "NoneAsEmptyString")]
     pub release_date: Option<chrono::DateTime<Utc>>,
+
+    /// Age rating in the region's own system, e.g. "PG-13" or "12A"
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub certification: Option<String>,
+
+    /// Free text TMDB users add, e.g. the festival a premiere was at or "Blu-ray & DVD"
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub note: Option<String>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct TvSearchResult {
+    pub id: TMDBId,
+    pub name: String,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub first_air_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TvSearchResponse {
+    pub page: u32,
+    pub results: Vec<TvSearchResult>,
+    pub total_pages: u32,
+    pub total_results: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub enum TvStatus {
+    #[serde(rename = "Returning Series")]
+    ReturningSeries,
+    Planned,
+    #[serde(rename = "In Production")]
+    InProduction,
+    Ended,
+    Canceled,
+    Pilot,
+    #[serde(other)]
+    Unknown,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct TvSeries {
+    pub id: TMDBId,
+    pub name: String,
+    pub status: TvStatus,
+    pub in_production: bool,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub first_air_date: Option<chrono::NaiveDate>,
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub last_air_date: Option<chrono::NaiveDate>,
+
+    pub number_of_seasons: u32,
+    pub number_of_episodes: u32,
+    pub seasons: Vec<SeasonSummary>,
+    pub networks: Vec<Network>,
+
+    pub last_episode_to_air: Option<Episode>,
+    /// Only set once TMDB knows when the next episode airs
+    pub next_episode_to_air: Option<Episode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Network {
+    pub id: TMDBId,
+    pub name: String,
+    /// Country code, empty for streaming services
+    pub origin_country: String,
+}
+
+/// A season as listed on its series, without the episodes
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct SeasonSummary {
+    pub id: TMDBId,
+    pub name: String,
+    /// 0 for specials
+    pub season_number: u32,
+    pub episode_count: u32,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub air_date: Option<chrono::NaiveDate>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct Season {
+    pub id: TMDBId,
+    pub name: String,
+    pub season_number: u32,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub air_date: Option<chrono::NaiveDate>,
+
+    pub episodes: Vec<Episode>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct Episode {
+    pub id: TMDBId,
+    pub name: String,
+    pub season_number: u32,
+    pub episode_number: u32,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub air_date: Option<chrono::NaiveDate>,
+
+    /// In minutes, unknown until the episode has aired
+    pub runtime: Option<u32>,
+}
+
+impl Episode {
+    /// The first episode of a season, except for season 0 which is where TMDB keeps a show's specials
+    pub fn is_season_premiere(&self) -> bool {
+        self.season_number > 0 && self.episode_number == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use crate::mock_server::{AVENGERS_MOVIE, SEVERANCE_SEARCH};
+
+    use super::{Episode, Movie, ReleaseType, TvSearchResponse};
+
+    fn avengers() -> Movie {
+        serde_json::from_str(AVENGERS_MOVIE).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Option<DateTime<Utc>> {
+        Some(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn resolves_releases_per_region() {
+        let movie = avengers();
+
+        assert_eq!(date(2012, 5, 4), movie.theatrical_release("US"));
+        assert_eq!(date(2012, 4, 26), movie.theatrical_release("GB"));
+        assert_eq!(date(2012, 9, 25), movie.digital_release("US"));
+        assert_eq!(None, movie.digital_release("GB"));
+        assert_eq!(date(2012, 4, 11), movie.earliest_premiere());
+    }
+
+    #[test]
+    fn theatrical_release_falls_back_to_the_primary_date_then_the_earliest_anywhere() {
+        let mut movie = avengers();
+        assert_eq!(date(2012, 4, 25), movie.theatrical_release("FR"));
+
+        movie.release_date = None;
+        assert_eq!(date(2012, 4, 26), movie.theatrical_release("FR"));
+    }
+
+    #[test]
+    fn deserializes_certifications_and_notes() {
+        let movie = avengers();
+
+        let physical = movie
+            .release_in("GB", &[ReleaseType::Physical])
+            .expect("no british physical release");
+        assert_eq!(Some("12".to_owned()), physical.certification);
+        assert_eq!(Some("Blu-ray & DVD".to_owned()), physical.note);
+
+        let premiere = movie
+            .release_in("US", &[ReleaseType::Premiere])
+            .expect("no american premiere");
+        assert_eq!(None, premiere.certification);
+        assert_eq!(Some("El Capitan Theatre".to_owned()), premiere.note);
+    }
+
+    #[test]
+    fn specials_are_not_season_premieres() {
+        let episode = |season_number: u32, episode_number: u32| -> Episode {
+            serde_json::from_value(serde_json::json!({
+                "id": 1,
+                "name": "Episode",
+                "season_number": season_number,
+                "episode_number": episode_number,
+                "air_date": "",
+                "runtime": null,
+            }))
+            .unwrap()
+        };
+
+        assert!(episode(2, 1).is_season_premiere());
+        assert!(!episode(2, 2).is_season_premiere());
+        assert!(!episode(0, 1).is_season_premiere());
+    }
+
+    #[test]
+    fn tv_search_responses_are_paged() {
+        let response: TvSearchResponse = serde_json::from_str(SEVERANCE_SEARCH).unwrap();
+
+        assert_eq!(
+            (1, 1, 2),
+            (response.page, response.total_pages, response.total_results)
+        );
+    }
 }
@@ -0,0 +1,206 @@
+use futures::stream::{self, Stream, TryStreamExt};
+
+use crate::client::{MovieClient, TMDBClient};
+use crate::errors::TMDBClientError;
+use crate::model::MovieSearchResult;
+
+/// Parameters for [`MovieClient::search_movies`], everything but the query being optional
+#[derive(Debug, Clone, Default)]
+pub struct MovieSearch {
+    pub query: String,
+    /// 1-based page to fetch, TMDB's first page if unset
+    pub page: Option<u32>,
+    /// Matches movies released in this year in any region
+    pub year: Option<u32>,
+    /// Matches movies whose primary release date is in this year
+    pub primary_release_year: Option<u32>,
+    /// ISO 3166-1 code used to pick release dates
+    pub region: Option<String>,
+    /// ISO 639-1 code with an optional region, e.g. "en-US", for translated titles
+    pub language: Option<String>,
+}
+
+impl MovieSearch {
+    pub fn new(query: &str) -> Self {
+        MovieSearch {
+            query: query.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    pub fn year(mut self, year: u32) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    pub fn primary_release_year(mut self, year: u32) -> Self {
+        self.primary_release_year = Some(year);
+        self
+    }
+
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_owned());
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_owned());
+        self
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let optional = [
+            ("page", self.page.map(|page| page.to_string())),
+            ("year", self.year.map(|year| year.to_string())),
+            (
+                "primary_release_year",
+                self.primary_release_year.map(|year| year.to_string()),
+            ),
+            ("region", self.region.clone()),
+            ("language", self.language.clone()),
+        ];
+
+        let mut params = vec![
+            ("include_adult", "false".to_owned()),
+            ("query", self.query.clone()),
+        ];
+        params.extend(
+            optional
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|value| (name, value))),
+        );
+        params
+    }
+}
+
+impl TMDBClient {
+    /// Streams every result of `search`, starting at its page (or the first) and fetching the next page only once the
+    /// current one has been consumed. The stream ends after TMDB's last page or after yielding the first error.
+    pub fn search_movie_pages(
+        &self,
+        search: MovieSearch,
+    ) -> impl Stream<Item = Result<MovieSearchResult, TMDBClientError>> {
+        let client = self.clone();
+        let first_page = search.page.unwrap_or(1);
+
+        stream::try_unfold(Some(first_page), move |page| {
+            next_page(client.clone(), search.clone(), page)
+        })
+        .map_ok(|results| stream::iter(results.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+/// Fetches `page`, along with the number of the page after it if TMDB has one
+async fn next_page(
+    client: TMDBClient,
+    search: MovieSearch,
+    page: Option<u32>,
+) -> Result<Option<(Vec<MovieSearchResult>, Option<u32>)>, TMDBClientError> {
+    let page = match page {
+        Some(page) => page,
+        None => return Ok(None),
+    };
+
+    let response = client.search_movies(&search.page(page)).await?;
+    let next_page = if response.page < response.total_pages {
+        Some(response.page + 1)
+    } else {
+        None
+    };
+
+    Ok(Some((response.results, next_page)))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::MockServer;
+
+    use crate::client::MovieClient;
+    use crate::mock_server::*;
+
+    use super::MovieSearch;
+
+    fn ids(results: &[crate::model::MovieSearchResult]) -> Vec<u32> {
+        results.iter().map(|result| u32::from(result.id)).collect()
+    }
+
+    #[test]
+    fn only_sends_parameters_that_are_set() {
+        let params = MovieSearch::new("dune")
+            .primary_release_year(1984)
+            .language("en-US")
+            .params();
+
+        assert_eq!(
+            vec![
+                ("include_adult", "false".to_owned()),
+                ("query", "dune".to_owned()),
+                ("primary_release_year", "1984".to_owned()),
+                ("language", "en-US".to_owned()),
+            ],
+            params
+        );
+    }
+
+    #[tokio::test]
+    async fn narrows_searches_by_year() {
+        let server = MockServer::start().await;
+        mock_search_with(
+            &server,
+            "dune",
+            &[("primary_release_year", "1984")],
+            DUNE_1984_SEARCH,
+        )
+        .await;
+        let client = offline_client(&server);
+
+        let response = client
+            .search_movies(&MovieSearch::new("dune").primary_release_year(1984))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            (1, 1, 1),
+            (response.page, response.total_pages, response.total_results)
+        );
+        assert_eq!(vec![841], ids(&response.results));
+    }
+
+    #[tokio::test]
+    async fn streams_every_page() {
+        let server = MockServer::start().await;
+        mock_search_with(&server, "dune", &[("page", "1")], DUNE_SEARCH_PAGE_1).await;
+        mock_search_with(&server, "dune", &[("page", "2")], DUNE_SEARCH_PAGE_2).await;
+        let client = offline_client(&server);
+
+        let results: Vec<_> = client
+            .search_movie_pages(MovieSearch::new("dune"))
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(vec![438631, 841, 693134, 172533], ids(&results));
+        assert_eq!(2, server.received_requests().await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn streams_lazily() {
+        let server = MockServer::start().await;
+        mock_search_with(&server, "dune", &[("page", "1")], DUNE_SEARCH_PAGE_1).await;
+        mock_search_with(&server, "dune", &[("page", "2")], DUNE_SEARCH_PAGE_2).await;
+        let client = offline_client(&server);
+
+        let mut pages = Box::pin(client.search_movie_pages(MovieSearch::new("dune")));
+        let first = pages.try_next().await.unwrap().unwrap();
+
+        assert_eq!(438631, u32::from(first.id));
+        assert_eq!(1, server.received_requests().await.unwrap().len());
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+use crate::schema::Schema;
+
+mod schema;
+
+const IGDB_PROTO_URL: &str = "https://api.igdb.com/v4/igdbapi.proto";
+
+/// Syncs the checked-in IGDB proto with the one IGDB publishes, listing what changed between them
+#[derive(Parser, Debug)]
+pub struct ExecutableArgs {
+    /// URL or local path of the proto to sync from
+    #[arg(long, default_value = IGDB_PROTO_URL)]
+    pub source: String,
+
+    /// Checked-in proto to diff against and rewrite
+    #[arg(long, default_value = "src/igdb/protos/igdbapi.proto")]
+    pub proto: PathBuf,
+
+    /// Only report the changes, failing if there are any, instead of rewriting the proto
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = ExecutableArgs::parse();
+
+    let upstream_text = load(&args.source).await?;
+    let upstream_dir = tempfile::tempdir()?;
+    let upstream_path = upstream_dir.path().join("igdbapi.proto");
+    std::fs::write(&upstream_path, &upstream_text)?;
+
+    // Parsing before writing means a broken download never replaces the checked-in proto
+    let upstream = Schema::parse(&upstream_path)?;
+    let current = Schema::parse(&args.proto)?;
+    let changes = current.diff(&upstream);
+
+    if changes.is_empty() {
+        println!(
+            "No changes to messages or enums in {}",
+            args.proto.display()
+        );
+    }
+    for change in &changes {
+        println!("{}", change);
+    }
+
+    if args.check {
+        if !changes.is_empty() {
+            return Err(eyre!(
+                "{} is out of date with {}",
+                args.proto.display(),
+                args.source
+            ));
+        }
+    } else {
+        std::fs::write(&args.proto, upstream_text)?;
+        println!("Wrote {}", args.proto.display());
+    }
+
+    Ok(())
+}
+
+/// Reads the proto from a URL or a file, dropping the byte order mark IGDB's copy starts with
+async fn load(source: &str) -> Result<String> {
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?
+    } else {
+        std::fs::read_to_string(source)?
+    };
+
+    Ok(text.trim_start_matches('\u{feff}').to_owned())
+}
@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use protobuf::descriptor::field_descriptor_proto::{Label, Type};
+use protobuf::descriptor::FieldDescriptorProto;
+
+const PACKAGE: &str = "igdb";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Message,
+    Enum,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Message => write!(f, "message"),
+            Kind::Enum => write!(f, "enum"),
+        }
+    }
+}
+
+/// A field of a message, or a value of an enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub number: i32,
+    /// Type of a field, e.g. `repeated Platform`, and empty for enum values
+    pub type_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub kind: Kind,
+    pub members: BTreeMap<String, Member>,
+}
+
+/// The messages and enums of the `igdb` package, which is all the sync cares about
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub definitions: BTreeMap<String, Definition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added {
+        kind: Kind,
+        name: String,
+    },
+    Removed {
+        kind: Kind,
+        name: String,
+    },
+    MemberAdded {
+        parent: String,
+        member: String,
+        number: i32,
+    },
+    MemberRemoved {
+        parent: String,
+        member: String,
+        number: i32,
+    },
+    Renumbered {
+        parent: String,
+        member: String,
+        from: i32,
+        to: i32,
+    },
+    Retyped {
+        parent: String,
+        member: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added { kind, name } => write!(f, "+ {} {}", kind, name),
+            Change::Removed { kind, name } => write!(f, "- {} {}", kind, name),
+            Change::MemberAdded {
+                parent,
+                member,
+                number,
+            } => write!(f, "+ {}.{} = {}", parent, member, number),
+            Change::MemberRemoved {
+                parent,
+                member,
+                number,
+            } => write!(f, "- {}.{} = {}", parent, member, number),
+            Change::Renumbered {
+                parent,
+                member,
+                from,
+                to,
+            } => {
+                write!(f, "~ {}.{} renumbered {} -> {}", parent, member, from, to)
+            }
+            Change::Retyped {
+                parent,
+                member,
+                from,
+                to,
+            } => {
+                write!(f, "~ {}.{} retyped {} -> {}", parent, member, from, to)
+            }
+        }
+    }
+}
+
+impl Schema {
+    /// Parses a proto file without `protoc`, the same way the igdb crate's build script does
+    pub fn parse(path: &Path) -> Result<Self> {
+        let include = path
+            .parent()
+            .ok_or_else(|| eyre!("{} has no parent directory", path.display()))?;
+        let descriptors = protobuf_parse::Parser::new()
+            .pure()
+            .include(include)
+            .input(path)
+            .file_descriptor_set()
+            .map_err(|e| eyre!("Couldn't parse {}: {:#}", path.display(), e))?;
+
+        let mut schema = Schema::default();
+        for file in descriptors
+            .file
+            .iter()
+            .filter(|file| file.package() == PACKAGE)
+        {
+            for message in &file.message_type {
+                let members = message
+                    .field
+                    .iter()
+                    .map(|field| {
+                        let member = Member {
+                            number: field.number(),
+                            type_name: field_type(field),
+                        };
+                        (field.name().to_owned(), member)
+                    })
+                    .collect();
+                schema.insert(message.name(), Kind::Message, members);
+            }
+
+            for enum_type in &file.enum_type {
+                let members = enum_type
+                    .value
+                    .iter()
+                    .map(|value| {
+                        let member = Member {
+                            number: value.number(),
+                            type_name: String::new(),
+                        };
+                        (value.name().to_owned(), member)
+                    })
+                    .collect();
+                schema.insert(enum_type.name(), Kind::Enum, members);
+            }
+        }
+
+        Ok(schema)
+    }
+
+    fn insert(&mut self, name: &str, kind: Kind, members: BTreeMap<String, Member>) {
+        self.definitions
+            .insert(name.to_owned(), Definition { kind, members });
+    }
+
+    /// Lists what changes going from `self` to `other`. Removed definitions come first, then the other definitions in
+    /// name order, each with its removed members before its other member changes, which are also in name order.
+    pub fn diff(&self, other: &Schema) -> Vec<Change> {
+        let mut changes = vec![];
+
+        for (name, definition) in &self.definitions {
+            if !other.definitions.contains_key(name) {
+                changes.push(Change::Removed {
+                    kind: definition.kind,
+                    name: name.clone(),
+                });
+            }
+        }
+
+        for (name, new) in &other.definitions {
+            let Some(old) = self.definitions.get(name) else {
+                changes.push(Change::Added {
+                    kind: new.kind,
+                    name: name.clone(),
+                });
+                continue;
+            };
+
+            for (member, old_member) in &old.members {
+                if !new.members.contains_key(member) {
+                    changes.push(Change::MemberRemoved {
+                        parent: name.clone(),
+                        member: member.clone(),
+                        number: old_member.number,
+                    });
+                }
+            }
+
+            for (member, new_member) in &new.members {
+                let Some(old_member) = old.members.get(member) else {
+                    changes.push(Change::MemberAdded {
+                        parent: name.clone(),
+                        member: member.clone(),
+                        number: new_member.number,
+                    });
+                    continue;
+                };
+
+                if old_member.number != new_member.number {
+                    changes.push(Change::Renumbered {
+                        parent: name.clone(),
+                        member: member.clone(),
+                        from: old_member.number,
+                        to: new_member.number,
+                    });
+                }
+                if old_member.type_name != new_member.type_name {
+                    changes.push(Change::Retyped {
+                        parent: name.clone(),
+                        member: member.clone(),
+                        from: old_member.type_name.clone(),
+                        to: new_member.type_name.clone(),
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+/// Renders a field's type roughly as it's written in the proto
+fn field_type(field: &FieldDescriptorProto) -> String {
+    let type_name = match field.type_() {
+        Type::TYPE_MESSAGE | Type::TYPE_ENUM => {
+            let type_name = field.type_name();
+            type_name
+                .strip_prefix(&format!(".{}.", PACKAGE))
+                .or_else(|| type_name.strip_prefix('.'))
+                .unwrap_or(type_name)
+                .to_owned()
+        }
+        scalar => format!("{:?}", scalar)
+            .trim_start_matches("TYPE_")
+            .to_lowercase(),
+    };
+
+    match field.label() {
+        Label::LABEL_REPEATED => format!("repeated {}", type_name),
+        _ => type_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, Kind, Schema};
+
+    fn parse(proto: &str) -> Schema {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("igdbapi.proto");
+        std::fs::write(&path, proto).unwrap();
+        Schema::parse(&path).unwrap()
+    }
+
+    const CURRENT: &str = r#"
+syntax = "proto3";
+package igdb;
+import "google/protobuf/timestamp.proto";
+
+message Game {
+    uint64 id = 1;
+    string name = 2;
+    google.protobuf.Timestamp first_release_date = 3;
+    GameCategoryEnum category = 4;
+    int32 follows = 5;
+}
+
+message Collection {
+    uint64 id = 1;
+}
+
+enum GameCategoryEnum {
+    MAIN_GAME = 0;
+    DLC_ADDON = 1;
+}
+"#;
+
+    #[test]
+    fn parses_messages_and_enums() {
+        let schema = parse(CURRENT);
+
+        let game = &schema.definitions["Game"];
+        assert_eq!(Kind::Message, game.kind);
+        assert_eq!(3, game.members["first_release_date"].number);
+        assert_eq!(
+            "google.protobuf.Timestamp",
+            game.members["first_release_date"].type_name
+        );
+        assert_eq!("GameCategoryEnum", game.members["category"].type_name);
+        assert_eq!("int32", game.members["follows"].type_name);
+
+        let category = &schema.definitions["GameCategoryEnum"];
+        assert_eq!(Kind::Enum, category.kind);
+        assert_eq!(1, category.members["DLC_ADDON"].number);
+    }
+
+    #[test]
+    fn no_changes_for_the_same_schema() {
+        assert!(parse(CURRENT).diff(&parse(CURRENT)).is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_renumbered_and_retyped_fields() {
+        let upstream = r#"
+syntax = "proto3";
+package igdb;
+import "google/protobuf/timestamp.proto";
+
+message Game {
+    uint64 id = 1;
+    string name = 2;
+    google.protobuf.Timestamp first_release_date = 6;
+    GameCategoryEnum category = 4;
+    repeated GameType game_types = 7;
+    int64 follows = 5;
+}
+
+message GameType {
+    uint64 id = 1;
+}
+
+enum GameCategoryEnum {
+    MAIN_GAME = 0;
+}
+"#;
+
+        let changes = parse(CURRENT).diff(&parse(upstream));
+
+        assert_eq!(
+            vec![
+                Change::Removed {
+                    kind: Kind::Message,
+                    name: "Collection".to_owned()
+                },
+                Change::Renumbered {
+                    parent: "Game".to_owned(),
+                    member: "first_release_date".to_owned(),
+                    from: 3,
+                    to: 6
+                },
+                Change::Retyped {
+                    parent: "Game".to_owned(),
+                    member: "follows".to_owned(),
+                    from: "int32".to_owned(),
+                    to: "int64".to_owned()
+                },
+                Change::MemberAdded {
+                    parent: "Game".to_owned(),
+                    member: "game_types".to_owned(),
+                    number: 7
+                },
+                Change::MemberRemoved {
+                    parent: "GameCategoryEnum".to_owned(),
+                    member: "DLC_ADDON".to_owned(),
+                    number: 1
+                },
+                Change::Added {
+                    kind: Kind::Message,
+                    name: "GameType".to_owned()
+                },
+            ],
+            changes
+        );
+        assert_eq!(
+            "~ Game.first_release_date renumbered 3 -> 6",
+            changes[1].to_string()
+        );
+    }
+}